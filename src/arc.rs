@@ -1,11 +1,13 @@
 //! provides the `Arc` class.
 use std::f64::consts::{FRAC_PI_2, PI};
 use std::iter::{empty, once};
-use utils::is_almost;
-use {Point, Segment};
+use utils::{is_almost, normalize_angle};
+use {Line, Point, Projection, Segment};
 
-/// Oriented arc segment. Always less than a half circle.
+/// Oriented arc segment.
+/// Arcs can turn in both directions and cover any angle (except full circles, see `Circle`).
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Arc {
     /// Starting point (hashed)
     pub start: Point,
@@ -15,17 +17,21 @@ pub struct Arc {
     pub center: Point,
     /// Radius
     pub radius: f64,
+    /// Do we turn clockwise (with respect to svg orientation) ?
+    /// Clockwise arcs go towards increasing angles.
+    pub clockwise: bool,
 }
 
 impl Arc {
-    /// Create a new arc.
-    pub fn new(start: Point, end: Point, center: Point, radius: f64) -> Arc {
+    /// Create a new arc turning in given direction.
+    pub fn new(start: Point, end: Point, center: Point, radius: f64, clockwise: bool) -> Arc {
         assert!(start != end);
         let mut arc = Arc {
             start,
             end,
             center,
             radius,
+            clockwise,
         };
         if !(is_almost(arc.center.distance_to(&arc.start), arc.radius)
             && is_almost(arc.center.distance_to(&arc.end), arc.radius))
@@ -35,6 +41,33 @@ impl Arc {
         arc
     }
 
+    /// Create the arc of less than a half circle between given points.
+    /// Direction is deduced from points positions.
+    pub fn small(start: Point, end: Point, center: Point, radius: f64) -> Arc {
+        let clockwise = (start - center).cross_product(&(end - center)) > 0.0;
+        Arc::new(start, end, center, radius, clockwise)
+    }
+
+    /// Create the arc going from start to end and passing through given middle point.
+    /// Returns `None` if points are aligned.
+    pub fn through(start: Point, middle: Point, end: Point) -> Option<Arc> {
+        circle_center(&start, &middle, &end).map(|center| {
+            let clockwise = (middle - start).cross_product(&(end - middle)) > 0.0;
+            Arc::new(start, end, center, center.distance_to(&start), clockwise)
+        })
+    }
+
+    /// Return arc in opposite direction.
+    pub fn reverse(&self) -> Arc {
+        Arc {
+            start: self.end,
+            end: self.start,
+            center: self.center,
+            radius: self.radius,
+            clockwise: !self.clockwise,
+        }
+    }
+
     /// Given center was not completely right, move it slightly.
     /// This can happen for example when endpoints have been rounded.
     fn adjust_center(&mut self) {
@@ -51,29 +84,43 @@ impl Arc {
 
     /// Return array of the two centers we could have.
     fn possible_centers(&self) -> Vec<Point> {
-        // we do some geometry to avoid too complex equations.
-        // take start as origin
-        let support = self.end - self.start;
-        let middle = self.start.center_with(&self.end);
-        // find bisector
-        let bisector_point = middle + support.perpendicular_vector();
-        let line = Segment::new(middle, bisector_point);
-        let centers = line_circle_intersections(&line, &self.start, self.radius).collect();
-        centers
-    }
-
-    /// Return normalized angle of points with center.
+        let bisector = Line::bisector(&self.start, &self.end);
+        line_circle_intersections(&bisector, &self.start, self.radius).collect()
+    }
+
+    /// Return angle swept when going from start to end (in [0, 2PI)).
     pub fn angle(&self) -> f64 {
-        ((self.start - self.center).angle() - (self.end - self.center).angle()) % (2.0 * PI)
+        self.angle_to(&self.end)
+    }
+
+    /// Return angle swept when going from start to given point of our circle,
+    /// following our direction (in [0, 2PI)).
+    pub fn angle_to(&self, point: &Point) -> f64 {
+        sweeping_angle(&self.center, &self.start, point, self.clockwise)
     }
 
     /// Return the arc's length.
     pub fn length(&self) -> f64 {
-        let angle = self.angle();
-        if angle > PI {
-            ((2.0 * PI) - angle) * self.radius
+        self.angle() * self.radius
+    }
+
+    /// Return closest point on ourselves from given point.
+    pub fn projection(&self, point: &Point) -> Projection {
+        if *point != self.center {
+            let direction = point - self.center;
+            let circle_point = self.center + direction * (self.radius / direction.norm());
+            if self.contains_circle_point(&circle_point) {
+                return Projection::new(
+                    point,
+                    circle_point,
+                    self.angle_to(&circle_point) * self.radius,
+                );
+            }
+        }
+        if point.distance_to(&self.start) <= point.distance_to(&self.end) {
+            Projection::new(point, self.start, 0.0)
         } else {
-            angle * self.radius
+            Projection::new(point, self.end, self.length())
         }
     }
 
@@ -99,9 +146,9 @@ impl Arc {
 
     /// Do we contain given point which is on our circle ?
     pub fn contains_circle_point(&self, point: &Point) -> bool {
-        let s = Segment::new(self.start, self.end);
-        let s2 = Segment::new(self.center, *point);
-        s.intersection_with(&s2).is_some()
+        self.start.is_almost(point)
+            || self.end.is_almost(point)
+            || self.angle_to(point) <= self.angle()
     }
 
     /// Intersect ourselves with horizontal line at given y.
@@ -150,19 +197,51 @@ impl Arc {
         &'a self,
         other: &'a Segment,
     ) -> impl Iterator<Item = Point> + 'a {
-        line_circle_intersections(other, &self.center, self.radius)
+        line_circle_intersections(&Line::supporting(other), &self.center, self.radius)
             .filter(move |p| self.contains_circle_point(p) && other.contains(p))
     }
 }
 
-fn line_circle_intersections<'a>(
-    segment: &'a Segment,
-    center: &'a Point,
+/// Return the center of the circle passing through the three given points
+/// or `None` if they are aligned.
+pub(crate) fn circle_center(p1: &Point, p2: &Point, p3: &Point) -> Option<Point> {
+    let b = p2 - p1;
+    let c = p3 - p1;
+    let denominator = 2.0 * b.cross_product(&c);
+    // test alignment on the sine of the angle between b and c to stay independent of scale
+    if denominator == 0.0 || is_almost(denominator / (2.0 * b.norm() * c.norm()), 0.0) {
+        None
+    } else {
+        let (b2, c2) = (b.scalar_product(&b), c.scalar_product(&c));
+        Some(Point::new(
+            p1.x + (c.y * b2 - b.y * c2) / denominator,
+            p1.y + (b.x * c2 - c.x * b2) / denominator,
+        ))
+    }
+}
+
+/// Return angle swept when turning around center from start to end in given direction
+/// (in [0, 2PI)).
+pub(crate) fn sweeping_angle(center: &Point, start: &Point, end: &Point, clockwise: bool) -> f64 {
+    let start_angle = (start - center).angle();
+    let end_angle = (end - center).angle();
+    if clockwise {
+        normalize_angle(end_angle - start_angle).value()
+    } else {
+        normalize_angle(start_angle - end_angle).value()
+    }
+}
+
+/// Iterate on all intersections between circle and line supporting given segment.
+pub(crate) fn line_circle_intersections(
+    line: &Line,
+    center: &Point,
     radius: f64,
-) -> impl Iterator<Item = Point> + 'a {
-    let d = segment.end - segment.start;
-    let c = center - segment.start;
-    // segment points are at alpha * d
+) -> impl Iterator<Item = Point> {
+    let start = line.point;
+    let d = line.direction;
+    let c = center - start;
+    // line points are at alpha * d
     // distance(alpha * d, center) = r
 
     // (xc-alpha*xd)**2 + (yc-alpha*yd)**2 - r**2 = 0
@@ -174,7 +253,7 @@ fn line_circle_intersections<'a>(
     let b = (c.x * d.x + c.y * d.y) * (-2.0);
     let c = c.x * c.x + c.y * c.y - radius * radius;
     let solutions = solve_quadratic_equation(a, b, c);
-    solutions.into_iter().map(move |s| segment.start + d * s)
+    solutions.into_iter().map(move |s| start + d * s)
 }
 
 fn solve_quadratic_equation(a: f64, b: f64, c: f64) -> Vec<f64> {
@@ -195,7 +274,8 @@ fn solve_quadratic_equation(a: f64, b: f64, c: f64) -> Vec<f64> {
     }
 }
 
-fn circles_intersections(
+/// Iterate on all intersections between two circles.
+pub(crate) fn circles_intersections(
     c1: &Point,
     c2: &Point,
     r1: f64,
@@ -0,0 +1,402 @@
+//! provides the `Bezier` class for cubic (and quadratic) Bezier curves.
+use std::iter::once;
+use tycat::Tycat;
+use utils::is_almost;
+use {Arc, Circle, ElementaryPath, Point, Projection, Quadrant, Segment, Vector};
+
+/// Maximal distance between a flat piece of curve and its chord when intersecting.
+const INTERSECTION_FLATNESS: f64 = 0.001;
+
+/// Gauss-Legendre nodes and weights on [-1, 1] used for length computations.
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// Oriented cubic Bezier curve.
+/// Quadratic curves are stored as (exactly equivalent) cubic ones.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Bezier {
+    /// Starting point
+    pub start: Point,
+    /// First control point (start tangent)
+    pub control1: Point,
+    /// Second control point (end tangent)
+    pub control2: Point,
+    /// Ending point
+    pub end: Point,
+}
+
+impl Bezier {
+    /// Create a new cubic Bezier curve.
+    pub fn new(start: Point, control1: Point, control2: Point, end: Point) -> Bezier {
+        assert!(start != end);
+        Bezier {
+            start,
+            control1,
+            control2,
+            end,
+        }
+    }
+
+    /// Create a new quadratic Bezier curve (degree is elevated to get a cubic one).
+    pub fn quadratic(start: Point, control: Point, end: Point) -> Bezier {
+        Bezier::new(
+            start,
+            start + (control - start) * (2.0 / 3.0),
+            end + (control - end) * (2.0 / 3.0),
+            end,
+        )
+    }
+
+    /// Return curve in opposite direction.
+    pub fn reverse(&self) -> Bezier {
+        Bezier::new(self.end, self.control2, self.control1, self.start)
+    }
+
+    /// Return point at given parameter (between 0 and 1).
+    pub fn point_at(&self, t: f64) -> Point {
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        Point::new(
+            a * self.start.x + b * self.control1.x + c * self.control2.x + d * self.end.x,
+            a * self.start.y + b * self.control1.y + c * self.control2.y + d * self.end.y,
+        )
+    }
+
+    /// Return derivative at given parameter.
+    pub fn derivative_at(&self, t: f64) -> Vector {
+        let u = 1.0 - t;
+        (self.control1 - self.start) * (3.0 * u * u)
+            + (self.control2 - self.control1) * (6.0 * u * t)
+            + (self.end - self.control2) * (3.0 * t * t)
+    }
+
+    /// Return second derivative at given parameter.
+    fn second_derivative_at(&self, t: f64) -> Vector {
+        let first = (self.control2 - self.control1) - (self.control1 - self.start);
+        let second = (self.end - self.control2) - (self.control2 - self.control1);
+        first * (6.0 * (1.0 - t)) + second * (6.0 * t)
+    }
+
+    /// Return tangent vector when leaving start point.
+    /// Degenerate control points are skipped.
+    pub fn start_tangent(&self) -> Vector {
+        once(self.control1)
+            .chain(once(self.control2))
+            .find(|c| !c.is_almost(&self.start))
+            .unwrap_or(self.end)
+            - self.start
+    }
+
+    /// Return tangent vector when arriving at end point.
+    pub fn end_tangent(&self) -> Vector {
+        self.end
+            - once(self.control2)
+                .chain(once(self.control1))
+                .find(|c| !c.is_almost(&self.end))
+                .unwrap_or(self.start)
+    }
+
+    /// Split curve in two at given parameter.
+    pub fn split_at(&self, t: f64) -> (Bezier, Bezier) {
+        let lerp = |p1: &Point, p2: &Point| p1 + (p2 - p1) * t;
+        let p01 = lerp(&self.start, &self.control1);
+        let p12 = lerp(&self.control1, &self.control2);
+        let p23 = lerp(&self.control2, &self.end);
+        let p012 = lerp(&p01, &p12);
+        let p123 = lerp(&p12, &p23);
+        let middle = lerp(&p012, &p123);
+        (
+            Bezier {
+                start: self.start,
+                control1: p01,
+                control2: p012,
+                end: middle,
+            },
+            Bezier {
+                start: middle,
+                control1: p123,
+                control2: p23,
+                end: self.end,
+            },
+        )
+    }
+
+    /// Return the part of the curve between the two given parameters.
+    pub fn sub_curve(&self, t1: f64, t2: f64) -> Bezier {
+        let (_, end_part) = self.split_at(t1);
+        if is_almost(t1, 1.0) {
+            end_part
+        } else {
+            end_part.split_at((t2 - t1) / (1.0 - t1)).0
+        }
+    }
+
+    /// Return curve's length.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{Bezier, Point};
+    /// // a quadratic curve which is in fact a straight line
+    /// let b = Bezier::quadratic(Point::new(0.0, 0.0), Point::new(1.0, 1.0), Point::new(2.0, 2.0));
+    /// assert!((b.length() - 8.0f64.sqrt()).abs() < 0.000001);
+    /// ```
+    pub fn length(&self) -> f64 {
+        self.length_until(1.0)
+    }
+
+    /// Return length of the curve between start and given parameter.
+    pub fn length_until(&self, t: f64) -> f64 {
+        // integrate derivative's norm over 8 intervals with 5 points Gauss-Legendre each
+        let intervals = 8;
+        let step = t / f64::from(intervals);
+        (0..intervals)
+            .map(|i| {
+                let middle = step * (f64::from(i) + 0.5);
+                GAUSS_LEGENDRE
+                    .iter()
+                    .map(|&(x, w)| w * self.derivative_at(middle + x * step / 2.0).norm())
+                    .sum::<f64>()
+                    * step
+                    / 2.0
+            })
+            .sum()
+    }
+
+    /// Return signed area between the curve and the origin
+    /// (see `ElementaryPath::signed_area`).
+    pub(crate) fn signed_area(&self) -> f64 {
+        // x*dy - y*dx is a polynomial of degree 5 : gauss-legendre is exact
+        GAUSS_LEGENDRE
+            .iter()
+            .map(|&(x, w)| {
+                let t = (x + 1.0) / 2.0;
+                let point = self.point_at(t);
+                let derivative = self.derivative_at(t);
+                w * (point.x * derivative.y - point.y * derivative.x)
+            })
+            .sum::<f64>()
+            / 4.0
+    }
+
+    /// Return the parameter of the point at given distance from start.
+    pub fn parameter_at_length(&self, length: f64) -> f64 {
+        // length is increasing with the parameter : we can bisect
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..40 {
+            let middle = (low + high) / 2.0;
+            if self.length_until(middle) < length {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    /// Return the parameter of the point of the curve nearest from given point.
+    pub fn parameter_of(&self, point: &Point) -> f64 {
+        let samples = 16;
+        let mut t = (0..=samples)
+            .map(|i| f64::from(i) / f64::from(samples))
+            .min_by(|t1, t2| {
+                self.point_at(*t1)
+                    .distance_to(point)
+                    .partial_cmp(&self.point_at(*t2).distance_to(point))
+                    .unwrap()
+            })
+            .unwrap();
+        // refine with newton on the derivative of the squared distance
+        for _ in 0..8 {
+            let difference = self.point_at(t) - point;
+            let derivative = self.derivative_at(t);
+            let numerator = difference.scalar_product(&derivative);
+            let denominator = derivative.scalar_product(&derivative)
+                + difference.scalar_product(&self.second_derivative_at(t));
+            if denominator.abs() < 1e-12 {
+                break;
+            }
+            t = (t - numerator / denominator).clamp(0.0, 1.0);
+        }
+        t
+    }
+
+    /// Return closest point on ourselves from given point.
+    pub fn projection(&self, point: &Point) -> Projection {
+        let t = self.parameter_of(point);
+        [(self.point_at(t), t), (self.start, 0.0), (self.end, 1.0)]
+            .iter()
+            .map(|&(closest_point, t)| Projection::new(point, closest_point, self.length_until(t)))
+            .min_by(|p1, p2| p1.distance.partial_cmp(&p2.distance).unwrap())
+            .unwrap()
+    }
+
+    /// Return maximal distance between control points and the chord.
+    fn flatness(&self) -> f64 {
+        let chord = self.end - self.start;
+        let squared_length = chord.scalar_product(&chord);
+        [self.control1, self.control2]
+            .iter()
+            .map(|c| {
+                if is_almost(squared_length, 0.0) {
+                    c.distance_to(&self.start)
+                } else {
+                    let alpha =
+                        ((c - self.start).scalar_product(&chord) / squared_length).clamp(0.0, 1.0);
+                    c.distance_to(&(self.start + chord * alpha))
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Convert curve to a polyline (start and end included)
+    /// such that no point of the curve is further than given distance from it.
+    /// pre-condition: given distance is strictly positive.
+    pub fn flatten(&self, max_error: f64) -> Vec<Point> {
+        assert!(max_error > 0.0);
+        let mut points = vec![self.start];
+        self.flatten_into(max_error, &mut points);
+        points
+    }
+
+    /// Add flattened points (except start) into given vector.
+    fn flatten_into(&self, max_error: f64, points: &mut Vec<Point>) {
+        // distance between curve and chord is at most 3/4 of control points distance
+        if self.flatness() * 0.75 <= max_error {
+            points.push(self.end);
+        } else {
+            let (first_half, second_half) = self.split_at(0.5);
+            first_half.flatten_into(max_error, points);
+            second_half.flatten_into(max_error, points);
+        }
+    }
+
+    /// Return all flat pieces of the curve (with their parameters range)
+    /// which could intersect given quadrant.
+    fn flat_pieces(&self, quadrant: &Quadrant) -> Vec<(f64, f64, Bezier)> {
+        let mut pieces = Vec::new();
+        let mut remaining = vec![(0.0, 1.0, *self)];
+        while let Some((t1, t2, piece)) = remaining.pop() {
+            if !piece.quadrant().intersects(quadrant) {
+                continue;
+            }
+            if piece.flatness() <= INTERSECTION_FLATNESS || t2 - t1 < 1e-9 {
+                pieces.push((t1, t2, piece));
+            } else {
+                let middle = (t1 + t2) / 2.0;
+                let (first_half, second_half) = piece.split_at(0.5);
+                remaining.push((t1, middle, first_half));
+                remaining.push((middle, t2, second_half));
+            }
+        }
+        pieces
+    }
+
+    /// Find all intersections with given path by intersecting flat pieces
+    /// and then refining each point with newton's method on given implicit equation.
+    fn refined_intersections<F: Fn(&Point) -> (f64, Vector)>(
+        &self,
+        other: &ElementaryPath,
+        implicit_equation: F,
+    ) -> Vec<Point> {
+        let mut intersections: Vec<Point> = Vec::new();
+        for (t1, t2, piece) in self.flat_pieces(&other.quadrant()) {
+            if piece.start == piece.end {
+                continue;
+            }
+            let chord = ElementaryPath::Segment(Segment::new(piece.start, piece.end));
+            for approximation in other.intersections_with(&chord) {
+                let ratio = piece.start.distance_to(&approximation) / piece.length();
+                let mut t = t1 + (t2 - t1) * ratio.min(1.0);
+                for _ in 0..8 {
+                    let (value, gradient) = implicit_equation(&self.point_at(t));
+                    let derivative = gradient.scalar_product(&self.derivative_at(t));
+                    if derivative.abs() < 1e-12 {
+                        break;
+                    }
+                    t = (t - value / derivative).clamp(0.0, 1.0);
+                }
+                let intersection = self.point_at(t);
+                if is_almost(implicit_equation(&intersection).0, 0.0)
+                    && other.contains(&intersection)
+                    && !intersections.iter().any(|i| i.is_almost(&intersection))
+                {
+                    intersections.push(intersection);
+                }
+            }
+        }
+        intersections
+    }
+
+    /// Return all points obtained when intersecting with given Segment.
+    pub fn intersections_with_segment(&self, other: &Segment) -> Vec<Point> {
+        let direction = other.end - other.start;
+        let length = direction.norm();
+        let normal = direction.perpendicular_vector() / length;
+        self.refined_intersections(&ElementaryPath::Segment(*other), |p| {
+            ((p - other.start).scalar_product(&normal), normal)
+        })
+    }
+
+    /// Return all points obtained when intersecting with given Arc.
+    pub fn intersections_with_arc(&self, other: &Arc) -> Vec<Point> {
+        self.refined_intersections(&ElementaryPath::Arc(*other), |p| {
+            circle_equation(&other.center, other.radius, p)
+        })
+    }
+
+    /// Return all points obtained when intersecting with given Circle.
+    pub fn intersections_with_circle(&self, other: &Circle) -> Vec<Point> {
+        self.refined_intersections(&ElementaryPath::Circle(*other), |p| {
+            circle_equation(&other.center, other.radius, p)
+        })
+    }
+
+    /// Return all points obtained when intersecting with given Bezier curve.
+    pub fn intersections_with_bezier(&self, other: &Bezier) -> Vec<Point> {
+        let mut intersections: Vec<Point> = Vec::new();
+        for (t1, t2, piece) in self.flat_pieces(&other.quadrant()) {
+            if piece.start == piece.end {
+                continue;
+            }
+            for approximation in
+                other.intersections_with_segment(&Segment::new(piece.start, piece.end))
+            {
+                // solve self(t) = other(s) with newton
+                let ratio = piece.start.distance_to(&approximation) / piece.length();
+                let mut t = t1 + (t2 - t1) * ratio.min(1.0);
+                let mut s = other.parameter_of(&approximation);
+                for _ in 0..8 {
+                    let difference = self.point_at(t) - other.point_at(s);
+                    let dt = self.derivative_at(t);
+                    let ds = other.derivative_at(s) * -1.0;
+                    let determinant = dt.cross_product(&ds);
+                    if determinant.abs() < 1e-12 {
+                        break;
+                    }
+                    t = (t - difference.cross_product(&ds) / determinant).clamp(0.0, 1.0);
+                    s = (s - dt.cross_product(&difference) / determinant).clamp(0.0, 1.0);
+                }
+                let intersection = self.point_at(t);
+                if intersection.is_almost(&other.point_at(s))
+                    && !intersections.iter().any(|i| i.is_almost(&intersection))
+                {
+                    intersections.push(intersection);
+                }
+            }
+        }
+        intersections
+    }
+}
+
+/// Return distance to circle (implicit equation) and its gradient at given point.
+fn circle_equation(center: &Point, radius: f64, point: &Point) -> (f64, Vector) {
+    let difference = point - center;
+    let distance = difference.norm();
+    (distance - radius, difference / distance)
+}
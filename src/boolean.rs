@@ -0,0 +1,493 @@
+//! Boolean operations (union, intersection, difference, xor) between sets of shapes.
+//!
+//! All edges are cut at their intersections. We then keep each small edge
+//! if the operation's result differs on its two sides and orient it so that
+//! the result lies on its right (inside for clockwise shapes).
+//! Kept edges are finally followed into loops.
+use holed_pocket::build_holed_pockets;
+use holed_polygon::build_holed_polygons;
+use intersections::intersect_paths;
+use std::collections::HashMap;
+use std::iter::once;
+use utils::{is_almost, normalize_angle};
+use {
+    ElementaryPath, HoledPocket, HoledPolygon, Pocket, Point, PointsHash, Polygon, Ray, Segment,
+    Vector,
+};
+
+/// Available boolean operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Everything inside first or second operand
+    Union,
+    /// Everything inside first and second operands
+    Intersection,
+    /// Everything inside first operand but not inside second one
+    Difference,
+    /// Everything inside exactly one operand
+    Xor,
+}
+
+impl Operation {
+    /// Is a point inside the result given if it is inside each operand ?
+    fn keeps(self, in_first: bool, in_second: bool) -> bool {
+        match self {
+            Operation::Union => in_first || in_second,
+            Operation::Intersection => in_first && in_second,
+            Operation::Difference => in_first && !in_second,
+            Operation::Xor => in_first != in_second,
+        }
+    }
+}
+
+/// Distance from edges at which we look for their inside and outside.
+const SIDE_CHECK: f64 = 0.00001;
+
+/// Direction of rays used for computing windings.
+/// We avoid horizontal or vertical ones since they often align with edges.
+const RAY_DIRECTION: Vector = Vector {
+    x: 0.813_264_2,
+    y: 0.581_895_1,
+};
+
+/// Compute given operation between two sets of holed polygons.
+/// Each set's holed polygons are allowed to overlap.
+///
+/// # Example
+/// ```
+/// use grouille::boolean::{polygons_operation, Operation};
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let big = vec![HoledPolygon::new(Polygon::square(0.0, 0.0, 4.0), Vec::new())];
+/// let small = vec![HoledPolygon::new(Polygon::square(1.0, 1.0, 2.0), Vec::new())];
+/// let frame = polygons_operation(&big, &small, Operation::Difference, &mut hasher);
+/// assert_eq!(frame.len(), 1);
+/// assert_eq!(frame[0].holes.len(), 1);
+/// assert!((frame[0].outer_polygon.area() + frame[0].holes[0].area() - 12.0).abs() < 0.00001);
+/// let shifted = vec![HoledPolygon::new(Polygon::square(2.0, 2.0, 4.0), Vec::new())];
+/// let union = polygons_operation(&big, &shifted, Operation::Union, &mut hasher);
+/// assert_eq!(union.len(), 1);
+/// assert!((union[0].outer_polygon.area() - 28.0).abs() < 0.00001);
+/// ```
+pub fn polygons_operation(
+    first: &[HoledPolygon],
+    second: &[HoledPolygon],
+    operation: Operation,
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPolygon> {
+    let first_loops = holed_polygons_loops(first, points_hasher);
+    let second_loops = holed_polygons_loops(second, points_hasher);
+    let polygons = loops_operation(&first_loops, &second_loops, operation, points_hasher)
+        .into_iter()
+        .map(|edge| {
+            let mut polygon = loop_polygon(&edge);
+            if !polygon.is_oriented_clockwise() {
+                polygon.reverse();
+            }
+            polygon
+        })
+        .collect();
+    build_holed_polygons(polygons)
+}
+
+/// Return union of two sets of holed polygons.
+pub fn polygons_union(
+    first: &[HoledPolygon],
+    second: &[HoledPolygon],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPolygon> {
+    polygons_operation(first, second, Operation::Union, points_hasher)
+}
+
+/// Return intersection of two sets of holed polygons.
+pub fn polygons_intersection(
+    first: &[HoledPolygon],
+    second: &[HoledPolygon],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPolygon> {
+    polygons_operation(first, second, Operation::Intersection, points_hasher)
+}
+
+/// Return first set of holed polygons minus second one.
+pub fn polygons_difference(
+    first: &[HoledPolygon],
+    second: &[HoledPolygon],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPolygon> {
+    polygons_operation(first, second, Operation::Difference, points_hasher)
+}
+
+/// Return symmetric difference of two sets of holed polygons.
+pub fn polygons_xor(
+    first: &[HoledPolygon],
+    second: &[HoledPolygon],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPolygon> {
+    polygons_operation(first, second, Operation::Xor, points_hasher)
+}
+
+/// Compute given operation between two sets of holed pockets.
+/// Each set's holed pockets are allowed to overlap.
+/// Arcs stay exact (they are only cut at intersections).
+///
+/// # Example
+/// ```
+/// use grouille::boolean::{pockets_operation, Operation};
+/// use grouille::{Circle, ElementaryPath, HoledPocket, Pocket, Point, PointsHash};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let disk = |x: f64| {
+///     let circle = Circle::new(Point::new(x + 1.0, 0.0), Point::new(x, 0.0), 1.0, true);
+///     vec![HoledPocket::new(Pocket::new(vec![ElementaryPath::Circle(circle)]), Vec::new())]
+/// };
+/// let lens = pockets_operation(&disk(0.0), &disk(1.0), Operation::Intersection, &mut hasher);
+/// assert_eq!(lens.len(), 1);
+/// assert_eq!(lens[0].outer_pocket.edge().len(), 2);
+/// let expected_area = 2.0 * std::f64::consts::PI / 3.0 - 3.0f64.sqrt() / 2.0;
+/// assert!((lens[0].outer_pocket.area() - expected_area).abs() < 0.00001);
+/// ```
+pub fn pockets_operation(
+    first: &[HoledPocket],
+    second: &[HoledPocket],
+    operation: Operation,
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    let first_loops = holed_pockets_loops(first, points_hasher);
+    let second_loops = holed_pockets_loops(second, points_hasher);
+    let pockets = loops_operation(&first_loops, &second_loops, operation, points_hasher)
+        .into_iter()
+        .map(|edge| {
+            let mut pocket = Pocket::new(edge);
+            if !pocket.is_oriented_clockwise() {
+                pocket.reverse();
+            }
+            pocket
+        })
+        .collect();
+    build_holed_pockets(pockets)
+}
+
+/// Return union of two sets of holed pockets.
+pub fn pockets_union(
+    first: &[HoledPocket],
+    second: &[HoledPocket],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    pockets_operation(first, second, Operation::Union, points_hasher)
+}
+
+/// Return intersection of two sets of holed pockets.
+pub fn pockets_intersection(
+    first: &[HoledPocket],
+    second: &[HoledPocket],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    pockets_operation(first, second, Operation::Intersection, points_hasher)
+}
+
+/// Return first set of holed pockets minus second one.
+pub fn pockets_difference(
+    first: &[HoledPocket],
+    second: &[HoledPocket],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    pockets_operation(first, second, Operation::Difference, points_hasher)
+}
+
+/// Return symmetric difference of two sets of holed pockets.
+pub fn pockets_xor(
+    first: &[HoledPocket],
+    second: &[HoledPocket],
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    pockets_operation(first, second, Operation::Xor, points_hasher)
+}
+
+/// Convert holed pockets into loops of paths with hashed endpoints.
+fn holed_pockets_loops(
+    holed_pockets: &[HoledPocket],
+    points_hasher: &mut PointsHash,
+) -> Vec<Pocket> {
+    holed_pockets
+        .iter()
+        .flat_map(|h| once(&h.outer_pocket).chain(h.holes.iter()))
+        .map(|pocket| {
+            Pocket::new(
+                pocket
+                    .edge()
+                    .iter()
+                    .filter_map(|path| match *path {
+                        // circles are kept untouched since their start is not a real vertex
+                        ElementaryPath::Circle(_) => Some(*path),
+                        _ => {
+                            let start = points_hasher.add(*path.start());
+                            let end = points_hasher.add(*path.end());
+                            if start == end {
+                                None
+                            } else {
+                                Some(path.sub_path(start, end))
+                            }
+                        }
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Convert holed polygons into loops of (hashed) segments.
+fn holed_polygons_loops(
+    holed_polygons: &[HoledPolygon],
+    points_hasher: &mut PointsHash,
+) -> Vec<Pocket> {
+    holed_polygons
+        .iter()
+        .flat_map(|h| once(&h.outer_polygon).chain(h.holes.iter()))
+        .map(|polygon| {
+            let points: Vec<Point> = polygon
+                .points()
+                .iter()
+                .map(|p| points_hasher.add(*p))
+                .collect();
+            Pocket::new(
+                points
+                    .iter()
+                    .zip(points.iter().cycle().skip(1))
+                    .filter(|(start, end)| start != end)
+                    .map(|(start, end)| ElementaryPath::Segment(Segment::new(*start, *end)))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Convert loop of segments to polygon, removing points in the middle of aligned segments.
+fn loop_polygon(edge: &[ElementaryPath]) -> Polygon {
+    let points: Vec<Point> = edge.iter().map(|p| *p.start()).collect();
+    let count = points.len();
+    Polygon::new(
+        (0..count)
+            .filter(|&i| {
+                let before = points[i] - points[(i + count - 1) % count];
+                let after = points[(i + 1) % count] - points[i];
+                before.scalar_product(&after) < 0.0
+                    || !is_almost(
+                        before.cross_product(&after) / (before.norm() * after.norm()),
+                        0.0,
+                    )
+            })
+            .map(|i| points[i])
+            .collect(),
+    )
+}
+
+/// Compute given operation between regions delimited by two sets of loops.
+/// Clockwise loops add their inside to the region, counter clockwise ones remove it.
+/// Return the edges of all resulting loops (clockwise outer loops and counter clockwise holes).
+pub(crate) fn loops_operation(
+    first: &[Pocket],
+    second: &[Pocket],
+    operation: Operation,
+    points_hasher: &mut PointsHash,
+) -> Vec<Vec<ElementaryPath>> {
+    let paths: Vec<ElementaryPath> = first
+        .iter()
+        .chain(second.iter())
+        .flat_map(|pocket| pocket.edge().iter().cloned())
+        .collect();
+    let small_paths = split_at_vertices(&intersect_paths(&paths, points_hasher));
+    let inside =
+        |point: &Point| operation.keeps(winding(point, first) > 0, winding(point, second) > 0);
+    // classify the longest path of each chain : does the result lie on its right or left ?
+    let chains = chains(&small_paths);
+    let mut representatives: HashMap<usize, usize> = HashMap::new();
+    for (index, chain) in chains.iter().enumerate() {
+        let representative = representatives.entry(*chain).or_insert(index);
+        if small_paths[index].length() > small_paths[*representative].length() {
+            *representative = index;
+        }
+    }
+    let sides: HashMap<usize, (bool, bool)> = representatives
+        .into_iter()
+        .map(|(chain, representative)| {
+            let path = &small_paths[representative];
+            let length = path.length();
+            let middle = path.point_at(length / 2.0);
+            let step = (length / 4.0).min(SIDE_CHECK);
+            let tangent = path.point_at(length / 2.0 + step) - path.point_at(length / 2.0 - step);
+            let normal = tangent.perpendicular_vector() * (SIDE_CHECK / tangent.norm());
+            (
+                chain,
+                (
+                    inside(&(middle + normal)),
+                    inside(&(middle + normal * -1.0)),
+                ),
+            )
+        })
+        .collect();
+    // keep each edge once (shared edges appear twice)
+    let mut kept: Vec<ElementaryPath> = Vec::new();
+    let mut twins: HashMap<(Point, Point), Vec<usize>> = HashMap::new();
+    for (path, chain) in small_paths.into_iter().zip(chains) {
+        let length = path.length();
+        let middle = path.point_at(length / 2.0);
+        let oriented_path = match sides[&chain] {
+            (true, false) => path,
+            (false, true) => path.reverse(),
+            _ => continue,
+        };
+        let path_twins = twins
+            .entry((*oriented_path.start(), *oriented_path.end()))
+            .or_default();
+        if !path_twins
+            .iter()
+            .any(|&t| kept[t].point_at(length / 2.0).is_almost(&middle))
+        {
+            path_twins.push(kept.len());
+            kept.push(oriented_path);
+        }
+    }
+    build_loops(kept)
+}
+
+/// Return the winding number of all loops around given point.
+pub(crate) fn winding(point: &Point, loops: &[Pocket]) -> i32 {
+    let ray = Ray::new(*point, RAY_DIRECTION);
+    loops
+        .iter()
+        .filter(|pocket| {
+            let (xmin, xmax) = pocket.quadrant.limits(0);
+            let (ymin, ymax) = pocket.quadrant.limits(1);
+            point.x >= xmin && point.x <= xmax && point.y >= ymin && point.y <= ymax
+        })
+        .map(|pocket| ray.winding_number(pocket))
+        .sum()
+}
+
+/// Group given paths into chains of paths following each other through points
+/// where no other path starts or ends. Return the chain of each path.
+/// All paths of a chain are on the same sides of the shapes they come from.
+fn chains(paths: &[ElementaryPath]) -> Vec<usize> {
+    let mut starting: HashMap<Point, Vec<usize>> = HashMap::new();
+    let mut degrees: HashMap<Point, usize> = HashMap::new();
+    for (index, path) in paths.iter().enumerate() {
+        starting.entry(*path.start()).or_default().push(index);
+        *degrees.entry(*path.start()).or_default() += 1;
+        *degrees.entry(*path.end()).or_default() += 1;
+    }
+    let mut fathers: Vec<usize> = (0..paths.len()).collect();
+    for (index, path) in paths.iter().enumerate() {
+        if degrees[path.end()] == 2 {
+            if let Some(next_paths) = starting.get(path.end()) {
+                if next_paths.len() == 1 {
+                    let (root1, root2) = (
+                        chain_root(&mut fathers, index),
+                        chain_root(&mut fathers, next_paths[0]),
+                    );
+                    fathers[root1] = root2;
+                }
+            }
+        }
+    }
+    (0..paths.len())
+        .map(|index| chain_root(&mut fathers, index))
+        .collect()
+}
+
+/// Return the root of given path's chain in the union find forest.
+fn chain_root(fathers: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while fathers[root] != root {
+        root = fathers[root];
+    }
+    // compress path
+    let mut current = index;
+    while fathers[current] != root {
+        let father = fathers[current];
+        fathers[current] = root;
+        current = father;
+    }
+    root
+}
+
+/// Cut paths on all endpoints of other paths they contain.
+/// This is needed for overlapping paths which are not cut by `intersect_paths`.
+fn split_at_vertices(paths: &[ElementaryPath]) -> Vec<ElementaryPath> {
+    let mut vertices: Vec<Point> = paths
+        .iter()
+        .flat_map(|p| once(*p.start()).chain(once(*p.end())))
+        .collect();
+    vertices.sort();
+    vertices.dedup();
+    let mut small_paths = Vec::with_capacity(paths.len());
+    for path in paths {
+        let mut inner_vertices: Vec<(f64, Point)> = vertices
+            .iter()
+            .filter(|v| *v != path.start() && *v != path.end() && path.contains(v))
+            .map(|v| (path.distance_from_start(v), *v))
+            .collect();
+        if inner_vertices.is_empty() {
+            small_paths.push(*path);
+            continue;
+        }
+        inner_vertices.sort_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap());
+        let points: Vec<Point> = once(*path.start())
+            .chain(inner_vertices.into_iter().map(|(_, v)| v))
+            .chain(once(*path.end()))
+            .collect();
+        small_paths.extend(points.windows(2).map(|p| path.sub_path(p[0], p[1])));
+    }
+    small_paths
+}
+
+/// Follow oriented paths into loops.
+/// When several paths leave a point we turn as much as possible towards the inside
+/// which separates regions only touching on one point.
+fn build_loops(paths: Vec<ElementaryPath>) -> Vec<Vec<ElementaryPath>> {
+    let mut leaving: HashMap<Point, Vec<usize>> = HashMap::new();
+    for (index, path) in paths.iter().enumerate() {
+        leaving.entry(*path.start()).or_default().push(index);
+    }
+    let mut used: Vec<bool> = paths.iter().map(|_| false).collect();
+    let mut loops = Vec::new();
+    for first_index in 0..paths.len() {
+        if used[first_index] {
+            continue;
+        }
+        used[first_index] = true;
+        let mut edge = vec![paths[first_index]];
+        while edge.last().unwrap().end() != edge[0].start() {
+            let current = edge.last().unwrap();
+            let (back_tangent, back_destination) = current.end_angles();
+            let next_index = leaving
+                .get(current.end())
+                .into_iter()
+                .flatten()
+                .filter(|&&i| !used[i])
+                .max_by_key(|&&i| {
+                    let (tangent, destination) = paths[i].start_angles();
+                    (
+                        normalize_angle(tangent.value() - back_tangent.value()),
+                        normalize_angle(destination.value() - back_destination.value()),
+                    )
+                });
+            if let Some(&next_index) = next_index {
+                used[next_index] = true;
+                edge.push(paths[next_index]);
+            } else {
+                // cannot happen with exact intersections : each point is left as often as reached
+                debug_assert!(false, "boolean operation could not close a loop");
+                edge.clear();
+                break;
+            }
+        }
+        if !edge.is_empty() && !is_flat(&edge) {
+            loops.push(edge);
+        }
+    }
+    loops
+}
+
+/// Is given loop of zero area ?
+fn is_flat(edge: &[ElementaryPath]) -> bool {
+    edge.iter().map(|p| p.signed_area()).sum::<f64>().abs() < SIDE_CHECK * SIDE_CHECK
+}
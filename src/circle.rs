@@ -0,0 +1,107 @@
+//! provides the `Circle` class.
+use arc::{circles_intersections, line_circle_intersections, sweeping_angle};
+use std::f64::consts::PI;
+use utils::is_almost;
+use {Arc, Line, Point, Projection, Segment};
+
+/// Oriented full circle, starting and ending on the same point.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Circle {
+    /// Starting (and ending) point
+    pub start: Point,
+    /// Center
+    pub center: Point,
+    /// Radius
+    pub radius: f64,
+    /// Do we turn clockwise (with respect to svg orientation) ?
+    /// Clockwise circles go towards increasing angles.
+    pub clockwise: bool,
+}
+
+impl Circle {
+    /// Create a new circle starting at given point.
+    /// Starting point is moved on the circle if needed.
+    pub fn new(start: Point, center: Point, radius: f64, clockwise: bool) -> Circle {
+        assert!(radius > 0.0);
+        assert!(start != center);
+        let direction = start - center;
+        let start = center + direction * (radius / direction.norm());
+        Circle {
+            start,
+            center,
+            radius,
+            clockwise,
+        }
+    }
+
+    /// Return circle in opposite direction.
+    pub fn reverse(&self) -> Circle {
+        Circle {
+            clockwise: !self.clockwise,
+            ..*self
+        }
+    }
+
+    /// Return the circle's length.
+    pub fn length(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    /// Return angle swept when going from start to given point of our circle,
+    /// following our direction (in [0, 2PI)).
+    pub fn angle_to(&self, point: &Point) -> f64 {
+        sweeping_angle(&self.center, &self.start, point, self.clockwise)
+    }
+
+    /// Return closest point on ourselves from given point.
+    pub fn projection(&self, point: &Point) -> Projection {
+        if *point == self.center {
+            Projection::new(point, self.start, 0.0)
+        } else {
+            let direction = point - self.center;
+            let circle_point = self.center + direction * (self.radius / direction.norm());
+            Projection::new(
+                point,
+                circle_point,
+                self.angle_to(&circle_point) * self.radius,
+            )
+        }
+    }
+
+    /// Do we contain given point ?
+    pub fn contains(&self, point: &Point) -> bool {
+        is_almost(self.center.distance_to(point), self.radius)
+    }
+
+    /// Return the same circle but starting (and ending) at given point.
+    /// pre-condition: we contain given point.
+    pub fn starting_at(&self, start: Point) -> Circle {
+        Circle::new(start, self.center, self.radius, self.clockwise)
+    }
+
+    /// Return the arc obtained when going from start to end along us.
+    pub fn arc(&self, start: Point, end: Point) -> Arc {
+        Arc::new(start, end, self.center, self.radius, self.clockwise)
+    }
+
+    /// Iterate on all points obtained when intersecting with given Circle.
+    pub fn intersections_with_circle(&self, other: &Self) -> impl Iterator<Item = Point> {
+        circles_intersections(&self.center, &other.center, self.radius, other.radius)
+    }
+
+    /// Iterate on all points obtained when intersecting with given Arc.
+    pub fn intersections_with_arc<'a>(&self, other: &'a Arc) -> impl Iterator<Item = Point> + 'a {
+        circles_intersections(&self.center, &other.center, self.radius, other.radius)
+            .filter(move |p| other.contains_circle_point(p))
+    }
+
+    /// Iterate on all points obtained when intersecting with given Segment.
+    pub fn intersections_with_segment<'a>(
+        &'a self,
+        other: &'a Segment,
+    ) -> impl Iterator<Item = Point> + 'a {
+        line_circle_intersections(&Line::supporting(other), &self.center, self.radius)
+            .filter(move |p| other.contains(p))
+    }
+}
@@ -1,6 +1,7 @@
 //! This module allows for the initial classification
 //! of polygons into a polygon tree.
 use crate::shape::Shape;
+use itertools::repeat_call;
 use std::iter::repeat;
 
 /// Take some polygons to classify and return three vectors : classified ones, roots, fathers
@@ -62,3 +63,33 @@ pub fn brute_force_classification<'a, S: Shape>(
     }
     (classified, roots, fathers)
 }
+
+/// Classify given shapes and return for each shape its depth in the inclusion tree
+/// together with its father (itself for roots).
+pub(crate) fn inclusion_depths<S: Shape>(shapes: &[S]) -> (Vec<usize>, Vec<isize>) {
+    let shapes_number = shapes.len();
+    let (_, roots, fathers) = brute_force_classification(shapes);
+    // now build the inclusion tree in the right direction (towards children not towards fathers)
+    let mut children: Vec<Vec<usize>> = repeat_call(Vec::new).take(shapes_number).collect();
+
+    for index in 0..shapes_number {
+        if fathers[index] != index as isize {
+            children[fathers[index] as usize].push(index);
+        }
+    }
+
+    // compute level in tree for each node
+    let mut depths = vec![0; shapes_number]; // what depth in inclusion tree is each shape ?
+    fn set_depth(root: usize, depth: usize, depths: &mut [usize], children: &[Vec<usize>]) {
+        assert_eq!(depths[root], 0); // should not change twice
+        depths[root] = depth;
+        for child in &children[root] {
+            set_depth(*child, depth + 1, depths, children);
+        }
+    }
+
+    for root in roots {
+        set_depth(root, 0, &mut depths, &children);
+    }
+    (depths, fathers)
+}
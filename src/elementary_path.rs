@@ -1,20 +1,58 @@
 //! provides `ElementaryPath` structure for storing segments or arcs.
 use crate::utils::{normalize_angle, Angle};
 use std::f64::consts::{FRAC_PI_2, PI};
-use {Arc, Point, PointsHash, Segment, Vector};
+use {Arc, Bezier, Circle, Point, PointsHash, Projection, Segment, Vector};
 
 /// Elementary path (used for building larger paths)
 /// can be either:
 /// - `Arc`
+/// - `Bezier`
+/// - `Circle`
 /// - `Segment`
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ElementaryPath {
     /// `Arc` path
     Arc(Arc),
+    /// `Bezier` path
+    Bezier(Bezier),
+    /// `Circle` path
+    Circle(Circle),
     /// `Segment` path
     Segment(Segment),
 }
 
+/// Return points obtained when turning around center from given point
+/// such that chords are not further than given distance from the circle.
+/// pre-condition: given distance is strictly positive.
+fn flatten_circle(
+    center: &Point,
+    start: &Point,
+    radius: f64,
+    angle: f64,
+    clockwise: bool,
+    max_chord_error: f64,
+) -> Vec<Point> {
+    assert!(max_chord_error > 0.0);
+    // the chord error is radius * (1 - cos(step/2))
+    let max_step = if max_chord_error >= radius {
+        PI
+    } else {
+        2.0 * (1.0 - max_chord_error / radius).acos()
+    };
+    let chords = (angle / max_step).ceil().max(1.0) as usize;
+    let step = angle / chords as f64 * if clockwise { 1.0 } else { -1.0 };
+    (0..=chords)
+        .map(|i| start.rotate_around(center, step * i as f64))
+        .collect()
+}
+
+/// Return tangent angle when turning around center at given point.
+fn tangent_angle(center: &Point, point: &Point, clockwise: bool) -> f64 {
+    let direction = if clockwise { 1.0 } else { -1.0 };
+    (point - center).angle() + FRAC_PI_2 * direction
+}
+
 impl ElementaryPath {
     /// Create an `ElementaryPath` segment parallel to given one.
     /// at given distance and we can be on right or left side.
@@ -40,9 +78,19 @@ impl ElementaryPath {
         let destination_angle = normalize_angle((self.end() - self.start()).angle());
         match *self {
             ElementaryPath::Segment(_) => (destination_angle, destination_angle),
-            ElementaryPath::Arc(a) => {
-                let tangent_angle = normalize_angle((a.start - a.center).angle() - PI / 2.0);
-                (tangent_angle, destination_angle)
+            ElementaryPath::Arc(a) => (
+                normalize_angle(tangent_angle(&a.center, &a.start, a.clockwise)),
+                destination_angle,
+            ),
+            ElementaryPath::Bezier(b) => (
+                normalize_angle(b.start_tangent().angle()),
+                destination_angle,
+            ),
+            ElementaryPath::Circle(c) => {
+                // no destination here, we just follow the tangent
+                let tangent_angle =
+                    normalize_angle(tangent_angle(&c.center, &c.start, c.clockwise));
+                (tangent_angle, tangent_angle)
             }
         }
     }
@@ -55,9 +103,18 @@ impl ElementaryPath {
         let start_angle = normalize_angle((self.start() - self.end()).angle());
         match *self {
             ElementaryPath::Segment(_) => (start_angle, start_angle),
-            ElementaryPath::Arc(a) => {
-                let tangent_angle = normalize_angle((a.end - a.center).angle() + PI / 2.0);
-                (tangent_angle, start_angle)
+            ElementaryPath::Arc(a) => (
+                normalize_angle(tangent_angle(&a.center, &a.end, !a.clockwise)),
+                start_angle,
+            ),
+            ElementaryPath::Bezier(b) => (
+                normalize_angle((b.end_tangent() * -1.0).angle()),
+                start_angle,
+            ),
+            ElementaryPath::Circle(c) => {
+                let tangent_angle =
+                    normalize_angle(tangent_angle(&c.center, &c.start, !c.clockwise));
+                (tangent_angle, tangent_angle)
             }
         }
     }
@@ -67,6 +124,8 @@ impl ElementaryPath {
         match *self {
             ElementaryPath::Segment(ref s) => s.length(),
             ElementaryPath::Arc(ref a) => a.length(),
+            ElementaryPath::Bezier(ref b) => b.length(),
+            ElementaryPath::Circle(ref c) => c.length(),
         }
     }
 
@@ -75,6 +134,8 @@ impl ElementaryPath {
         match *self {
             ElementaryPath::Segment(ref s) => &s.start,
             ElementaryPath::Arc(ref a) => &a.start,
+            ElementaryPath::Bezier(ref b) => &b.start,
+            ElementaryPath::Circle(ref c) => &c.start,
         }
     }
 
@@ -83,16 +144,148 @@ impl ElementaryPath {
         match *self {
             ElementaryPath::Segment(ref s) => &s.end,
             ElementaryPath::Arc(ref a) => &a.end,
+            ElementaryPath::Bezier(ref b) => &b.end,
+            ElementaryPath::Circle(ref c) => &c.start,
+        }
+    }
+
+    /// Return distance travelled along ourselves when going from start to given point.
+    /// pre-condition: given point is on ourselves.
+    pub fn distance_from_start(&self, point: &Point) -> f64 {
+        match *self {
+            ElementaryPath::Segment(ref s) => s.start.distance_to(point),
+            ElementaryPath::Arc(ref a) => a.angle_to(point) * a.radius,
+            ElementaryPath::Bezier(ref b) => b.length_until(b.parameter_of(point)),
+            ElementaryPath::Circle(ref c) => c.angle_to(point) * c.radius,
+        }
+    }
+
+    /// Return path in opposite direction.
+    pub fn reverse(&self) -> ElementaryPath {
+        match *self {
+            ElementaryPath::Segment(ref s) => ElementaryPath::Segment(s.reverse()),
+            ElementaryPath::Arc(ref a) => ElementaryPath::Arc(a.reverse()),
+            ElementaryPath::Bezier(ref b) => ElementaryPath::Bezier(b.reverse()),
+            ElementaryPath::Circle(ref c) => ElementaryPath::Circle(c.reverse()),
+        }
+    }
+
+    /// Return point at given distance from start (travelling along ourselves).
+    pub fn point_at(&self, distance: f64) -> Point {
+        match *self {
+            ElementaryPath::Segment(ref s) => s.start + (s.end - s.start) * (distance / s.length()),
+            ElementaryPath::Arc(ref a) => {
+                let direction = if a.clockwise { 1.0 } else { -1.0 };
+                a.start
+                    .rotate_around(&a.center, direction * distance / a.radius)
+            }
+            ElementaryPath::Bezier(ref b) => b.point_at(b.parameter_at_length(distance)),
+            ElementaryPath::Circle(ref c) => {
+                let direction = if c.clockwise { 1.0 } else { -1.0 };
+                c.start
+                    .rotate_around(&c.center, direction * distance / c.radius)
+            }
+        }
+    }
+
+    /// Return signed area between ourselves and the origin.
+    /// Summing it on all paths of a closed edge gives the area it encloses
+    /// (positive if oriented clockwise).
+    pub fn signed_area(&self) -> f64 {
+        match *self {
+            ElementaryPath::Segment(ref s) => (s.start.x * s.end.y - s.start.y * s.end.x) / 2.0,
+            ElementaryPath::Arc(ref a) => {
+                // integrate x*dy - y*dx on the arc
+                let direction = if a.clockwise { 1.0 } else { -1.0 };
+                let chord = a.end - a.start;
+                (a.center.x * chord.y - a.center.y * chord.x
+                    + a.radius * a.radius * a.angle() * direction)
+                    / 2.0
+            }
+            ElementaryPath::Bezier(ref b) => b.signed_area(),
+            ElementaryPath::Circle(ref c) => {
+                let direction = if c.clockwise { 1.0 } else { -1.0 };
+                PI * c.radius * c.radius * direction
+            }
+        }
+    }
+
+    /// Convert ourselves to a polyline (start and end included)
+    /// such that no point of the path is further than given distance from it.
+    /// pre-condition: given distance is strictly positive.
+    pub fn flatten(&self, max_chord_error: f64) -> Vec<Point> {
+        match *self {
+            ElementaryPath::Segment(ref s) => vec![s.start, s.end],
+            ElementaryPath::Arc(ref a) => {
+                let mut points = flatten_circle(
+                    &a.center,
+                    &a.start,
+                    a.radius,
+                    a.angle(),
+                    a.clockwise,
+                    max_chord_error,
+                );
+                *points.last_mut().unwrap() = a.end;
+                points
+            }
+            ElementaryPath::Bezier(ref b) => b.flatten(max_chord_error),
+            ElementaryPath::Circle(ref c) => {
+                let mut points = flatten_circle(
+                    &c.center,
+                    &c.start,
+                    c.radius,
+                    2.0 * PI,
+                    c.clockwise,
+                    max_chord_error,
+                );
+                *points.last_mut().unwrap() = c.start;
+                points
+            }
+        }
+    }
+
+    /// Return closest point on ourselves from given point.
+    pub fn projection(&self, point: &Point) -> Projection {
+        match *self {
+            ElementaryPath::Segment(ref s) => s.projection(point),
+            ElementaryPath::Arc(ref a) => a.projection(point),
+            ElementaryPath::Bezier(ref b) => b.projection(point),
+            ElementaryPath::Circle(ref c) => c.projection(point),
+        }
+    }
+
+    /// Do we contain given point ?
+    pub fn contains(&self, point: &Point) -> bool {
+        match *self {
+            ElementaryPath::Segment(ref s) => s.contains(point),
+            ElementaryPath::Arc(ref a) => a.contains(point),
+            ElementaryPath::Bezier(ref b) => b.point_at(b.parameter_of(point)).is_almost(point),
+            ElementaryPath::Circle(ref c) => c.contains(point),
         }
     }
 
     /// Create a sub-path between given points.
     /// pre-condition: given points are on ourselves.
+    /// Circles are split into arcs unless both points are the same.
     pub fn sub_path(&self, start: Point, end: Point) -> ElementaryPath {
         match *self {
             ElementaryPath::Segment(_) => ElementaryPath::Segment(Segment::new(start, end)),
             ElementaryPath::Arc(ref a) => {
-                ElementaryPath::Arc(Arc::new(start, end, a.center, a.radius))
+                ElementaryPath::Arc(Arc::new(start, end, a.center, a.radius, a.clockwise))
+            }
+            ElementaryPath::Bezier(ref b) => {
+                let mut sub_curve = b.sub_curve(b.parameter_of(&start), b.parameter_of(&end));
+                // endpoints are kept exact
+                sub_curve.start = start;
+                sub_curve.end = end;
+                ElementaryPath::Bezier(sub_curve)
+            }
+            ElementaryPath::Circle(ref c) => {
+                if start == end {
+                    ElementaryPath::Circle(c.starting_at(start))
+                } else {
+                    ElementaryPath::Arc(c.arc(start, end))
+                }
             }
         }
     }
@@ -118,14 +311,52 @@ impl ElementaryPath {
                 ElementaryPath::Arc(ref a2) => {
                     Box::new(a.intersections_with_arc(a2)) as Box<dyn Iterator<Item = Point>>
                 }
+                ElementaryPath::Bezier(ref b2) => {
+                    Box::new(b2.intersections_with_arc(a).into_iter())
+                        as Box<dyn Iterator<Item = Point>>
+                }
+                ElementaryPath::Circle(ref c2) => {
+                    Box::new(c2.intersections_with_arc(a)) as Box<dyn Iterator<Item = Point>>
+                }
                 ElementaryPath::Segment(ref s2) => {
                     Box::new(a.intersections_with_segment(s2)) as Box<dyn Iterator<Item = Point>>
                 }
             },
+            ElementaryPath::Bezier(ref b) => Box::new(
+                match *other {
+                    ElementaryPath::Arc(ref a2) => b.intersections_with_arc(a2),
+                    ElementaryPath::Bezier(ref b2) => b.intersections_with_bezier(b2),
+                    ElementaryPath::Circle(ref c2) => b.intersections_with_circle(c2),
+                    ElementaryPath::Segment(ref s2) => b.intersections_with_segment(s2),
+                }
+                .into_iter(),
+            ) as Box<dyn Iterator<Item = Point>>,
+            ElementaryPath::Circle(ref c) => match *other {
+                ElementaryPath::Arc(ref a2) => {
+                    Box::new(c.intersections_with_arc(a2)) as Box<dyn Iterator<Item = Point>>
+                }
+                ElementaryPath::Bezier(ref b2) => {
+                    Box::new(b2.intersections_with_circle(c).into_iter())
+                        as Box<dyn Iterator<Item = Point>>
+                }
+                ElementaryPath::Circle(ref c2) => {
+                    Box::new(c.intersections_with_circle(c2)) as Box<dyn Iterator<Item = Point>>
+                }
+                ElementaryPath::Segment(ref s2) => {
+                    Box::new(c.intersections_with_segment(s2)) as Box<dyn Iterator<Item = Point>>
+                }
+            },
             ElementaryPath::Segment(ref s) => match *other {
                 ElementaryPath::Arc(ref a2) => {
                     Box::new(a2.intersections_with_segment(s)) as Box<dyn Iterator<Item = Point>>
                 }
+                ElementaryPath::Bezier(ref b2) => {
+                    Box::new(b2.intersections_with_segment(s).into_iter())
+                        as Box<dyn Iterator<Item = Point>>
+                }
+                ElementaryPath::Circle(ref c2) => {
+                    Box::new(c2.intersections_with_segment(s)) as Box<dyn Iterator<Item = Point>>
+                }
                 ElementaryPath::Segment(ref s2) => {
                     Box::new(s.intersection_with(s2).into_iter()) as Box<dyn Iterator<Item = Point>>
                 }
@@ -0,0 +1,209 @@
+//! Conversion of polylines into arcs and segments.
+//!
+//! Sliced models produce dense polylines where original designs had circles.
+//! We greedily cover points with the longest arc or segment staying within
+//! a given deviation from the original polyline, which gives much smaller paths
+//! (and smoother CNC moves).
+//! Closed polylines lying on a circle become a full `Circle` instead of arcs.
+//! Arcs and segments are joined on polyline points but are not required to be tangent
+//! to each other (these are not biarcs).
+use arc::circle_center;
+use std::f64::consts::PI;
+use streaming_iterator::StreamingIterator;
+use utils::iterators::GrouilleSlice;
+use {Arc, Circle, ElementaryPath, Path, Pocket, Point, Polygon, Segment};
+
+/// Convert given open chain of points into a path of arcs and segments.
+/// No point of the polyline is further than `max_deviation` from the returned paths.
+/// If the chain loops back to its start on a circle we return a single `Circle`.
+/// Repeated consecutive points are ignored.
+///
+/// # Example
+/// ```
+/// use grouille::{fitting::fit_points, ElementaryPath, Point};
+/// // points on a half circle, then on a line
+/// let mut points: Vec<Point> = (0..=20)
+///     .map(|i| std::f64::consts::PI * i as f64 / 20.0)
+///     .map(|a| Point::new(a.cos(), a.sin()))
+///     .collect();
+/// points.extend((1..=5).map(|i| Point::new(-1.0, -(i as f64))));
+/// let path = fit_points(&points, 0.01);
+/// assert_eq!(path.paths().len(), 2);
+/// if let ElementaryPath::Arc(ref a) = path.paths()[0] {
+///     assert!((a.radius - 1.0).abs() < 0.000001);
+/// } else {
+///     panic!("no arc found");
+/// }
+/// // a dense half circle is still a single arc
+/// let points: Vec<Point> = (0..1000)
+///     .map(|i| std::f64::consts::PI * i as f64 / 999.0)
+///     .map(|a| Point::new(a.cos(), a.sin()))
+///     .collect();
+/// assert_eq!(fit_points(&points, 0.01).paths().len(), 1);
+/// // repeated points
+/// let (p, q) = (Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+/// assert_eq!(fit_points(&[p, p, q], 0.01).paths().len(), 1);
+/// ```
+pub fn fit_points(points: &[Point], max_deviation: f64) -> Path {
+    let mut points = points.to_vec();
+    points.dedup();
+    let mut paths = Vec::new();
+    let mut start = 0;
+    while start + 1 < points.len() {
+        let (end, path) = longest_path(&points, start, max_deviation);
+        paths.push(path);
+        start = end;
+    }
+    Path::new(paths)
+}
+
+/// Convert given polygon into a pocket made of arcs and segments.
+/// No point of the polygon is further than `max_deviation` from the pocket's edge.
+/// A polygon approximating a circle becomes a pocket with a single `Circle` as edge.
+pub fn fit_polygon(polygon: &Polygon, max_deviation: f64) -> Pocket {
+    // start on the sharpest corner to avoid cutting an arc in two
+    let points = polygon.points();
+    let corner = points
+        .wrapping_windows(3)
+        .map(|p| turning_angle(&p[0], &p[1], &p[2]))
+        .cloned()
+        .enumerate()
+        .max_by(|(_, a1), (_, a2)| a1.partial_cmp(a2).unwrap())
+        .map(|(i, _)| (i + 1) % points.len())
+        .expect("empty polygon");
+    let paths = fit_points(&closed_chain(points, corner), max_deviation).paths;
+    if paths.len() == 1 {
+        return Pocket::new(paths);
+    }
+    // the corner might still be in the middle of an arc.
+    // we now know for sure the last path starts on a real junction so we fit again from there.
+    let last_start = paths.last().unwrap().start();
+    let junction = points.iter().position(|p| p == last_start).unwrap();
+    Pocket::new(fit_points(&closed_chain(points, junction), max_deviation).paths)
+}
+
+/// Return all points starting from given index and looping back to it.
+fn closed_chain(points: &[Point], start: usize) -> Vec<Point> {
+    points[start..]
+        .iter()
+        .chain(points[..=start].iter())
+        .cloned()
+        .collect()
+}
+
+/// Return absolute angle between incoming and outgoing directions at p2.
+fn turning_angle(p1: &Point, p2: &Point, p3: &Point) -> f64 {
+    let incoming = p2 - p1;
+    let outgoing = p3 - p2;
+    incoming
+        .cross_product(&outgoing)
+        .atan2(incoming.scalar_product(&outgoing))
+        .abs()
+}
+
+/// Return the longest (in number of points covered) path starting at given index
+/// together with the index of its last point.
+/// Segments are preferred over arcs.
+/// pre-condition: no two consecutive points are equal.
+fn longest_path(points: &[Point], start: usize, max_deviation: f64) -> (usize, ElementaryPath) {
+    let segment_end = (start + 1..points.len())
+        .take_while(|&end| fits_segment(&points[start..=end], max_deviation))
+        .last()
+        .unwrap_or(start + 1);
+    // a failing arc might be fixed by more points (giving a better center)
+    // so we try all ends up to where the polyline turns back, from the furthest one.
+    let arc = (segment_end + 1..=convex_end(points, start, max_deviation))
+        .rev()
+        .filter_map(|end| fit_arc(&points[start..=end], max_deviation).map(|path| (end, path)))
+        .next();
+    match arc {
+        Some((arc_end, path)) => (arc_end, path),
+        None => (
+            segment_end,
+            ElementaryPath::Segment(Segment::new(points[start], points[segment_end])),
+        ),
+    }
+}
+
+/// Return the index of the last point reached from given start while always turning
+/// in the same direction. Turns not deviating more than `max_deviation` are ignored.
+/// No arc can go further.
+fn convex_end(points: &[Point], start: usize, max_deviation: f64) -> usize {
+    let mut direction = None;
+    for end in start + 2..points.len() {
+        let corner = &points[end - 2..=end];
+        if fits_segment(corner, max_deviation) {
+            continue;
+        }
+        let clockwise = (corner[1] - corner[0]).cross_product(&(corner[2] - corner[1])) > 0.0;
+        if *direction.get_or_insert(clockwise) != clockwise {
+            return end - 1;
+        }
+    }
+    points.len() - 1
+}
+
+/// Are all given points close enough from the segment between first and last ones ?
+fn fits_segment(points: &[Point], max_deviation: f64) -> bool {
+    let start = points.first().unwrap();
+    let end = points.last().unwrap();
+    if start == end {
+        return false;
+    }
+    let direction = end - start;
+    let length = direction.norm();
+    points[1..points.len() - 1].iter().all(|p| {
+        let alpha = (p - start).scalar_product(&direction) / (length * length);
+        let distance = if alpha < 0.0 {
+            p.distance_to(start)
+        } else if alpha > 1.0 {
+            p.distance_to(end)
+        } else {
+            direction.cross_product(&(p - start)).abs() / length
+        };
+        distance <= max_deviation
+    })
+}
+
+/// Try fitting an arc (or a circle if we loop back to start) on given points.
+fn fit_arc(points: &[Point], max_deviation: f64) -> Option<ElementaryPath> {
+    let start = points.first().unwrap();
+    let end = points.last().unwrap();
+    let closed = start == end;
+    let (center, radius, clockwise) = if closed {
+        let (middle, other) = (&points[points.len() / 3], &points[2 * points.len() / 3]);
+        let center = circle_center(start, middle, other)?;
+        let clockwise = (middle - start).cross_product(&(other - middle)) > 0.0;
+        (center, center.distance_to(start), clockwise)
+    } else {
+        let arc = Arc::through(*start, points[points.len() / 2], *end)?;
+        (arc.center, arc.radius, arc.clockwise)
+    };
+    // all points and chord middles need to be close to the circle
+    // and we need to always turn in the same direction
+    let mut swept_angle = 0.0;
+    for p in points.windows(2) {
+        let (v1, v2) = (p[0] - center, p[1] - center);
+        let step = v1.cross_product(&v2);
+        if step == 0.0 || (step > 0.0) != clockwise {
+            return None;
+        }
+        swept_angle += step.atan2(v1.scalar_product(&v2)).abs();
+        for point in &[p[1], p[0].center_with(&p[1])] {
+            if (point.distance_to(&center) - radius).abs() > max_deviation {
+                return None;
+            }
+        }
+    }
+    if closed {
+        Some(ElementaryPath::Circle(Circle::new(
+            *start, center, radius, clockwise,
+        )))
+    } else if swept_angle < 2.0 * PI {
+        Some(ElementaryPath::Arc(Arc::new(
+            *start, *end, center, radius, clockwise,
+        )))
+    } else {
+        None
+    }
+}
@@ -1,8 +1,13 @@
 //! Provides `HoledPocket` structure.
-use crate::Pocket;
+use crate::classifier::inclusion_depths;
+use crate::offset::winding_region;
+use crate::{HoledPolygon, Pocket, Point, PointsHash, Projection};
+use itertools::repeat_call;
+use std::iter::once;
 
 /// pocket with (optional) holes inside.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HoledPocket {
     /// Outer pocket (required to be oriented clockwise)
     pub outer_pocket: Pocket,
@@ -13,11 +18,90 @@ pub struct HoledPocket {
 impl HoledPocket {
     /// Create a new holed polygon from given outer polygon and holes.
     pub fn new(outer_pocket: Pocket, holes: Vec<Pocket>) -> HoledPocket {
-        debug_assert!(outer_pocket.polygon_area() > 0.0);
-        debug_assert!(!holes.iter().any(|h| h.polygon_area() > 0.0));
+        debug_assert!(outer_pocket.is_oriented_clockwise());
+        debug_assert!(!holes.iter().any(|h| h.is_oriented_clockwise()));
         HoledPocket {
             outer_pocket,
             holes,
         }
     }
+
+    /// Return closest point on our edges from given point, together with the index
+    /// of the pocket containing it (0 for outer pocket, i+1 for hole i).
+    pub fn projection(&self, point: &Point) -> (usize, Projection) {
+        once(&self.outer_pocket)
+            .chain(self.holes.iter())
+            .map(|p| p.projection(point))
+            .enumerate()
+            .min_by(|(_, p1), (_, p2)| p1.distance.partial_cmp(&p2.distance).unwrap())
+            .unwrap()
+    }
+
+    /// Offset holed pocket inside by given radius (holes grow).
+    /// Arcs stay exact and the result can split into several holed pockets or vanish.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{Circle, ElementaryPath, HoledPocket, Pocket, Point, PointsHash};
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let circle = Circle::new(Point::new(2.0, 0.0), Point::new(0.0, 0.0), 2.0, true);
+    /// let disk = HoledPocket::new(Pocket::new(vec![ElementaryPath::Circle(circle)]), Vec::new());
+    /// let smaller_disks = disk.offset(0.5, &mut hasher);
+    /// assert_eq!(smaller_disks.len(), 1);
+    /// let expected_area = std::f64::consts::PI * 1.5 * 1.5;
+    /// assert!((smaller_disks[0].outer_pocket.area() - expected_area).abs() < 0.00001);
+    /// assert!(disk.offset(2.5, &mut hasher).is_empty());
+    /// ```
+    pub fn offset(&self, radius: f64, points_hasher: &mut PointsHash) -> Vec<HoledPocket> {
+        let raw_loops = once(&self.outer_pocket)
+            .chain(self.holes.iter())
+            .map(|p| p.inner_paths(radius, points_hasher))
+            .collect();
+        winding_region(raw_loops, points_hasher)
+    }
+
+    /// Grow holed pocket by given radius (holes shrink), with rounded corners.
+    pub fn outward_offset(&self, radius: f64, points_hasher: &mut PointsHash) -> Vec<HoledPocket> {
+        let raw_loops = once(&self.outer_pocket)
+            .chain(self.holes.iter())
+            .map(|p| p.outer_paths(radius, points_hasher))
+            .collect();
+        winding_region(raw_loops, points_hasher)
+    }
+
+    /// Convert ourselves to a holed polygon such that no point of our edges
+    /// is further than given distance from the polygon's edges.
+    pub fn to_holed_polygon(&self, max_chord_error: f64) -> HoledPolygon {
+        HoledPolygon::new(
+            self.outer_pocket.to_polygon(max_chord_error),
+            self.holes
+                .iter()
+                .map(|h| h.to_polygon(max_chord_error))
+                .collect(),
+        )
+    }
+}
+
+/// Turn some `Pocket`s into `HoledPocket`s by classifiying who is inside whom.
+/// All pockets need to be oriented clockwise.
+pub fn build_holed_pockets(pockets: Vec<Pocket>) -> Vec<HoledPocket> {
+    let (depths, fathers) = inclusion_depths(&pockets);
+    // pockets at even levels are outer part of holed pocket
+    // at odd levels they are holes
+    let mut holed_pockets_parts: Vec<(Option<Pocket>, Vec<Pocket>)> =
+        repeat_call(|| (None, Vec::new()))
+            .take(pockets.len())
+            .collect();
+    for (index, mut pocket) in pockets.into_iter().enumerate() {
+        if depths[index] % 2 == 0 {
+            holed_pockets_parts[index].0 = Some(pocket);
+        } else {
+            pocket.reverse();
+            holed_pockets_parts[fathers[index] as usize].1.push(pocket);
+        }
+    }
+    holed_pockets_parts
+        .into_iter()
+        .filter_map(|(pocket, children)| pocket.map(|p| HoledPocket::new(p, children)))
+        .collect()
 }
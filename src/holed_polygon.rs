@@ -1,14 +1,13 @@
 //! Provides `HoledPolygon` structure.
-use crate::pocket::pocket_builder::build_pockets;
-use classifier::brute_force_classification;
-use intersections::intersect_paths;
+use classifier::inclusion_depths;
 use itertools::repeat_call;
-use std::collections::HashMap;
-use std::iter::repeat;
-use {HoledPocket, Pocket, PointsHash, Polygon};
+use offset::{winding_region, Join};
+use std::iter::once;
+use {HoledPocket, Point, PointsHash, Polygon, Projection};
 
 /// polygon with (optional) holes inside.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HoledPolygon {
     /// Outer polygon (required to be oriented clockwise)
     pub outer_polygon: Polygon,
@@ -27,63 +26,69 @@ impl HoledPolygon {
         }
     }
 
-    /// Offset holed polygon into holed pockets
-    pub fn offset(&self, radius: f64, points_hasher: &mut PointsHash) -> Vec<HoledPocket> {
-        let mut paths = self.outer_polygon.inner_paths(radius, points_hasher);
-        for hole in &self.holes {
-            paths.append(&mut hole.inner_paths(radius, points_hasher));
-        }
-        let small_paths = intersect_paths(&paths, points_hasher);
-        let pockets = build_pockets(small_paths);
-        // only roots are kept (and their direct children as holes)
-        let (_, roots, fathers) = brute_force_classification(&pockets);
-        let mut holes: HashMap<usize, Vec<Pocket>> =
-            roots.iter().map(|k| (*k, Vec::new())).collect();
-        let mut holed_pockets: HashMap<usize, HoledPocket> = HashMap::new();
-        for ((index, pocket), father) in pockets.into_iter().enumerate().zip(fathers) {
-            if father as usize != index {
-                holes.get_mut(&(father as usize)).unwrap().push(pocket);
-            } else {
-                holed_pockets.insert(index, HoledPocket::new(pocket, Vec::new()));
-            }
-        }
-        let mut final_pockets = Vec::new();
-        for (father, (_, mut holed_pocket)) in holed_pockets.into_iter().enumerate() {
-            if let Some(pocket_holes) = holes.remove(&father) {
-                holed_pocket.holes = pocket_holes;
-            }
-            final_pockets.push(holed_pocket);
-        }
-        final_pockets
+    /// Return closest point on our edges from given point, together with the index
+    /// of the polygon containing it (0 for outer polygon, i+1 for hole i).
+    pub fn projection(&self, point: &Point) -> (usize, Projection) {
+        once(&self.outer_polygon)
+            .chain(self.holes.iter())
+            .map(|p| p.projection(point))
+            .enumerate()
+            .min_by(|(_, p1), (_, p2)| p1.distance.partial_cmp(&p2.distance).unwrap())
+            .unwrap()
     }
-}
 
-/// Turn some `Polygon`s into `HoledPolygon`s by classifiying who is inside whom.
-pub fn build_holed_polygons(polygons: Vec<Polygon>) -> Vec<HoledPolygon> {
-    let polygons_number = polygons.len();
-    let (_, roots, fathers) = brute_force_classification(&polygons);
-    // now build the inclusion tree in the right direction (towards children not towards fathers)
-    let mut children: Vec<Vec<usize>> = repeat_call(Vec::new).take(polygons_number).collect();
-
-    for index in 0..polygons_number {
-        if fathers[index] != index as isize {
-            children[fathers[index] as usize].push(index);
-        }
+    /// Convert ourselves to a holed pocket (with segments only).
+    pub fn to_holed_pocket(&self) -> HoledPocket {
+        HoledPocket::new(
+            self.outer_polygon.to_pocket(),
+            self.holes.iter().map(|h| h.to_pocket()).collect(),
+        )
     }
 
-    // compute level in tree for each node
-    let mut depths: Vec<usize> = repeat(0).take(polygons_number).collect(); // what depth in inclusion tree is each polygon ?
-    fn set_depth(root: usize, depth: usize, depths: &mut [usize], children: &[Vec<usize>]) {
-        assert_eq!(depths[root], 0); // should not change twice
-        depths[root] = depth;
-        for child in &children[root] {
-            set_depth(*child, depth + 1, depths, children);
-        }
+    /// Grow holed polygon by given radius (holes shrink), joining displaced edges
+    /// with given join around convex corners.
+    pub fn outward_offset(
+        &self,
+        radius: f64,
+        join: Join,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<HoledPocket> {
+        let raw_loops = once(&self.outer_polygon)
+            .chain(self.holes.iter())
+            .map(|p| p.outer_paths(radius, join, points_hasher))
+            .collect();
+        winding_region(raw_loops, points_hasher)
     }
 
-    for root in roots {
-        set_depth(root, 0, &mut depths, &children);
+    /// Offset holed polygon inside by given radius (holes grow) into holed pockets.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{HoledPolygon, PointsHash, Polygon};
+    /// use std::f64::consts::PI;
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let mut hole = Polygon::square(4.0, 4.0, 2.0);
+    /// hole.reverse();
+    /// let region = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), vec![hole]);
+    /// let offset = region.offset(1.0, &mut hasher);
+    /// assert_eq!(offset.len(), 1);
+    /// assert!((offset[0].outer_pocket.area() - 64.0).abs() < 0.00001);
+    /// assert_eq!(offset[0].holes.len(), 1);
+    /// assert!((offset[0].holes[0].area() + 12.0 + PI).abs() < 0.00001);
+    /// ```
+    pub fn offset(&self, radius: f64, points_hasher: &mut PointsHash) -> Vec<HoledPocket> {
+        let raw_loops = once(&self.outer_polygon)
+            .chain(self.holes.iter())
+            .map(|p| p.inner_paths(radius, points_hasher))
+            .collect();
+        winding_region(raw_loops, points_hasher)
     }
+}
+
+/// Turn some `Polygon`s into `HoledPolygon`s by classifiying who is inside whom.
+pub fn build_holed_polygons(polygons: Vec<Polygon>) -> Vec<HoledPolygon> {
+    let polygons_number = polygons.len();
+    let (depths, fathers) = inclusion_depths(&polygons);
 
     // polygons at even levels are outer part of holed polygon
     // at odd levels they are holes
@@ -0,0 +1,444 @@
+//! Graph of clipped infill pieces, traversed in few long continuous paths.
+//!
+//! Pattern pieces ending on the boundary are connected by boundary portions,
+//! chosen to decrease the number of paths needed to traverse all pieces.
+//! Remaining odd vertices are paired by following again the shortest paths
+//! between them (when not much longer than a travel move), leaving if possible only
+//! the two ends of one path in each connected part.
+//! Remaining ends are paired by travel moves and an eulerian circuit gives the final
+//! paths, split on travel moves.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::iter::once;
+use {ElementaryPath, HoledPocket, Path, Point, Projection};
+
+/// Maximal distance between a vertex and the boundary for it to be considered on it.
+const ON_BOUNDARY: f64 = 0.00001;
+
+/// Pieces are followed again between two odd vertices only if the path is not longer than
+/// this ratio times their distance. Otherwise we prefer a travel move.
+const MAX_DETOUR: f64 = 3.0;
+
+/// An edge between two vertices.
+#[derive(Debug)]
+struct Edge {
+    /// Start and end vertices
+    vertices: (usize, usize),
+    /// Paths from start to end vertex (empty for travel moves)
+    paths: Vec<ElementaryPath>,
+    /// Is it a copy of another edge (followed a second time) ?
+    duplicated: bool,
+}
+
+/// Graph whose edges are infill pieces and boundary portions.
+#[derive(Debug)]
+pub struct InfillGraph {
+    /// All vertices
+    vertices: Vec<Point>,
+    /// All edges
+    edges: Vec<Edge>,
+}
+
+impl InfillGraph {
+    /// Build graph from given pieces (clipped inside given pocket), adding
+    /// boundary portions between pieces ending on the boundary.
+    pub fn new(pocket: &HoledPocket, pieces: &[ElementaryPath]) -> Self {
+        let mut graph = InfillGraph {
+            vertices: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut indices = HashMap::new();
+        for piece in pieces {
+            let start = graph.vertex(&mut indices, piece.start());
+            let end = graph.vertex(&mut indices, piece.end());
+            graph.edges.push(Edge {
+                vertices: (start, end),
+                paths: vec![*piece],
+                duplicated: false,
+            });
+        }
+        while graph.add_boundary_portions(pocket) {}
+        graph
+    }
+
+    /// Connect odd vertices which are consecutive on the boundary, shortest connections first,
+    /// as long as it decreases the number of paths needed to traverse the graph.
+    /// Return if we added anything.
+    fn add_boundary_portions(&mut self, pocket: &HoledPocket) -> bool {
+        let mut added = false;
+        let mut degrees = self.degrees();
+        let mut candidates = Vec::new();
+        for boundary in once(&pocket.outer_pocket).chain(pocket.holes.iter()) {
+            // all odd vertices on this boundary, sorted along it
+            let mut odd_vertices: Vec<(usize, Projection)> = (0..self.vertices.len())
+                .filter(|&v| degrees[v] % 2 == 1)
+                .map(|v| {
+                    let point = self.vertices[v];
+                    (
+                        v,
+                        Projection {
+                            point,
+                            ..boundary.projection(&point)
+                        },
+                    )
+                })
+                .filter(|(_, projection)| projection.distance < ON_BOUNDARY)
+                .collect();
+            odd_vertices.sort_by(|(_, p1), (_, p2)| {
+                (p1.index, p1.parameter)
+                    .partial_cmp(&(p2.index, p2.parameter))
+                    .unwrap()
+            });
+            let count = odd_vertices.len();
+            if count < 2 {
+                continue;
+            }
+            let mut portions: Vec<(f64, usize, usize, Vec<ElementaryPath>)> = (0..count)
+                .filter_map(|index| {
+                    let (v1, p1) = odd_vertices[index];
+                    let (v2, p2) = odd_vertices[(index + 1) % count];
+                    let paths = boundary.part_between(&p1, &p2);
+                    if paths.is_empty() {
+                        None
+                    } else {
+                        let length: f64 = paths.iter().map(|p| p.length()).sum();
+                        Some((length, v1, v2, paths))
+                    }
+                })
+                .collect();
+            if count == 2 {
+                // both portions link the same vertices : keep the shortest one
+                portions.sort_by(|c1, c2| c1.0.partial_cmp(&c2.0).unwrap());
+                portions.truncate(1);
+            }
+            candidates.extend(portions);
+        }
+        candidates.sort_by(|c1, c2| c1.0.partial_cmp(&c2.0).unwrap());
+
+        // each connected component needs one path for each pair of odd vertices (at least one)
+        let mut fathers: Vec<usize> = (0..self.vertices.len()).collect();
+        for edge in &self.edges {
+            let (root1, root2) = (
+                root(&mut fathers, edge.vertices.0),
+                root(&mut fathers, edge.vertices.1),
+            );
+            fathers[root1] = root2;
+        }
+        let mut odd_counts = vec![0; self.vertices.len()];
+        for vertex in 0..self.vertices.len() {
+            if degrees[vertex] % 2 == 1 {
+                odd_counts[root(&mut fathers, vertex)] += 1;
+            }
+        }
+        for (_, v1, v2, paths) in candidates {
+            if degrees[v1] % 2 != 1 || degrees[v2] % 2 != 1 {
+                continue;
+            }
+            let (root1, root2) = (root(&mut fathers, v1), root(&mut fathers, v2));
+            if root1 != root2 {
+                fathers[root1] = root2;
+                odd_counts[root2] += odd_counts[root1] - 2;
+            } else if odd_counts[root1] >= 4 {
+                odd_counts[root1] -= 2;
+            } else {
+                continue; // we would only close a path into a cycle
+            }
+            degrees[v1] += 1;
+            degrees[v2] += 1;
+            self.edges.push(Edge {
+                vertices: (v1, v2),
+                paths,
+                duplicated: false,
+            });
+            added = true;
+        }
+        added
+    }
+
+    /// Return index of vertex at given point, adding it if needed.
+    fn vertex(&mut self, indices: &mut HashMap<Point, usize>, point: &Point) -> usize {
+        let vertices = &mut self.vertices;
+        *indices.entry(*point).or_insert_with(|| {
+            vertices.push(*point);
+            vertices.len() - 1
+        })
+    }
+
+    /// Return the degree of each vertex.
+    fn degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.vertices.len()];
+        for edge in &self.edges {
+            degrees[edge.vertices.0] += 1;
+            degrees[edge.vertices.1] += 1;
+        }
+        degrees
+    }
+
+    /// Return number of vertices of odd degree.
+    /// Traversing the graph requires at least half this number of paths.
+    pub fn odd_vertices_number(&self) -> usize {
+        self.degrees().iter().filter(|&d| d % 2 == 1).count()
+    }
+
+    /// Traverse all edges, returning few continuous paths (one per connected part if possible).
+    /// Some pieces might be followed twice to avoid travel moves : we return all paths in order,
+    /// each one with a boolean telling if it is printed (`false` for pieces already printed,
+    /// which should be followed without extruding).
+    /// Successive paths either follow each other or are linked by short travel moves.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::infill::{InfillGraph, Pattern};
+    /// use grouille::{HoledPolygon, PointsHash, Polygon};
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), Vec::new());
+    /// let pocket = square.to_holed_pocket();
+    /// let lines = Pattern::rectilinear(1.0).clip(&pocket, 0.0, &mut hasher);
+    /// // ten lines connected along the boundary
+    /// let paths = InfillGraph::new(&pocket, &lines).traversal();
+    /// assert_eq!(paths.len(), 1);
+    /// assert!(paths[0].1);
+    /// assert!((paths[0].0.length() - 109.0).abs() < 0.00001);
+    /// // hexagons have many inner vertices of degree 3
+    /// let hexagons = Pattern::honeycomb(1.0).clip(&pocket, 0.3, &mut hasher);
+    /// let paths = InfillGraph::new(&pocket, &hexagons).traversal();
+    /// let travels = paths
+    ///     .windows(2)
+    ///     .filter(|p| !p[0].0.end().is_almost(p[1].0.start()))
+    ///     .count();
+    /// assert!(travels <= 1);
+    /// assert!(paths.iter().any(|(_, printed)| !printed));
+    /// ```
+    pub fn traversal(mut self) -> Vec<(Path, bool)> {
+        self.add_shortest_paths();
+        // pair remaining odd vertices with travel moves, nearest first
+        let degrees = self.degrees();
+        let mut odd_vertices: Vec<usize> = (0..self.vertices.len())
+            .filter(|&v| degrees[v] % 2 == 1)
+            .collect();
+        while let Some(vertex) = odd_vertices.pop() {
+            let point = self.vertices[vertex];
+            let (closest_index, _) = odd_vertices
+                .iter()
+                .enumerate()
+                .min_by(|(_, &v1), (_, &v2)| {
+                    let d1 = point.distance_to(&self.vertices[v1]);
+                    let d2 = point.distance_to(&self.vertices[v2]);
+                    d1.partial_cmp(&d2).unwrap()
+                })
+                .unwrap();
+            let closest = odd_vertices.swap_remove(closest_index);
+            self.edges.push(Edge {
+                vertices: (vertex, closest),
+                paths: Vec::new(),
+                duplicated: false,
+            });
+        }
+
+        let mut adjacency = vec![Vec::new(); self.vertices.len()];
+        for (index, edge) in self.edges.iter().enumerate() {
+            adjacency[edge.vertices.0].push(index);
+            adjacency[edge.vertices.1].push(index);
+        }
+        let mut used = vec![false; self.edges.len()];
+        let mut paths = Vec::new();
+        for start in 0..self.vertices.len() {
+            if adjacency[start].iter().all(|&e| used[e]) {
+                continue;
+            }
+            let circuit = self.eulerian_circuit(start, &adjacency, &mut used);
+            paths.extend(self.split_circuit(&circuit));
+        }
+        paths
+    }
+
+    /// Pair odd vertices of each connected part by duplicating the edges on shortest paths
+    /// between them, closest pairs first, until only two of them are left in the part.
+    /// Paths much longer than the direct distance between their ends are not duplicated.
+    fn add_shortest_paths(&mut self) {
+        let degrees = self.degrees();
+        let odd_vertices: Vec<usize> = (0..self.vertices.len())
+            .filter(|&v| degrees[v] % 2 == 1)
+            .collect();
+        let mut fathers: Vec<usize> = (0..self.vertices.len()).collect();
+        for edge in &self.edges {
+            let (root1, root2) = (
+                root(&mut fathers, edge.vertices.0),
+                root(&mut fathers, edge.vertices.1),
+            );
+            fathers[root1] = root2;
+        }
+        let mut odd_counts = vec![0; self.vertices.len()];
+        for &vertex in &odd_vertices {
+            odd_counts[root(&mut fathers, vertex)] += 1;
+        }
+
+        let mut adjacency = vec![Vec::new(); self.vertices.len()];
+        for (index, edge) in self.edges.iter().enumerate() {
+            adjacency[edge.vertices.0].push(index);
+            adjacency[edge.vertices.1].push(index);
+        }
+        let lengths: Vec<f64> = self
+            .edges
+            .iter()
+            .map(|e| e.paths.iter().map(|p| p.length()).sum())
+            .collect();
+        let mut candidates = Vec::new();
+        let mut arriving_edges = Vec::with_capacity(odd_vertices.len());
+        for (index, &vertex) in odd_vertices.iter().enumerate() {
+            let (distances, arriving) = self.shortest_paths(vertex, &adjacency, &lengths);
+            candidates.extend(
+                odd_vertices
+                    .iter()
+                    .enumerate()
+                    .skip(index + 1)
+                    .filter(|&(_, &other)| {
+                        let direct = self.vertices[vertex].distance_to(&self.vertices[other]);
+                        distances[other] <= MAX_DETOUR * direct
+                    })
+                    .map(|(other_index, &other)| (distances[other], index, other_index)),
+            );
+            arriving_edges.push(arriving);
+        }
+        candidates.sort_by(|c1, c2| c1.0.partial_cmp(&c2.0).unwrap());
+
+        let mut paired = vec![false; odd_vertices.len()];
+        for (_, index1, index2) in candidates {
+            let part = root(&mut fathers, odd_vertices[index1]);
+            if paired[index1] || paired[index2] || odd_counts[part] <= 2 {
+                continue;
+            }
+            paired[index1] = true;
+            paired[index2] = true;
+            odd_counts[part] -= 2;
+            // walk back from second vertex to first one
+            let mut vertex = odd_vertices[index2];
+            while vertex != odd_vertices[index1] {
+                let edge_index = arriving_edges[index1][vertex].unwrap();
+                let edge = Edge {
+                    vertices: self.edges[edge_index].vertices,
+                    paths: self.edges[edge_index].paths.clone(),
+                    duplicated: true,
+                };
+                vertex = if edge.vertices.0 == vertex {
+                    edge.vertices.1
+                } else {
+                    edge.vertices.0
+                };
+                self.edges.push(edge);
+            }
+        }
+    }
+
+    /// Return distances from given vertex to all others (dijkstra's algorithm)
+    /// together with the edges arriving on each vertex on the shortest paths.
+    fn shortest_paths(
+        &self,
+        start: usize,
+        adjacency: &[Vec<usize>],
+        lengths: &[f64],
+    ) -> (Vec<f64>, Vec<Option<usize>>) {
+        let mut distances = vec![f64::INFINITY; self.vertices.len()];
+        let mut arriving_edges = vec![None; self.vertices.len()];
+        distances[start] = 0.0;
+        // bits of positive floats are ordered like the floats themselves
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0.0f64.to_bits(), start)));
+        while let Some(Reverse((distance_bits, vertex))) = heap.pop() {
+            let distance = f64::from_bits(distance_bits);
+            if distance > distances[vertex] {
+                continue;
+            }
+            for &edge_index in &adjacency[vertex] {
+                let (v1, v2) = self.edges[edge_index].vertices;
+                let neighbour = if v1 == vertex { v2 } else { v1 };
+                let new_distance = distance + lengths[edge_index];
+                if new_distance < distances[neighbour] {
+                    distances[neighbour] = new_distance;
+                    arriving_edges[neighbour] = Some(edge_index);
+                    heap.push(Reverse((new_distance.to_bits(), neighbour)));
+                }
+            }
+        }
+        (distances, arriving_edges)
+    }
+
+    /// Return eulerian circuit (edges and their traversal direction) of the connected
+    /// component containing given vertex (Hierholzer's algorithm).
+    /// pre-condition: all degrees are even.
+    fn eulerian_circuit(
+        &self,
+        start: usize,
+        adjacency: &[Vec<usize>],
+        used: &mut [bool],
+    ) -> Vec<(usize, bool)> {
+        let mut next_edges = vec![0; self.vertices.len()];
+        let mut stack: Vec<(usize, Option<(usize, bool)>)> = vec![(start, None)];
+        let mut circuit = Vec::new();
+        while let Some(&(vertex, arriving_edge)) = stack.last() {
+            let edges = &adjacency[vertex];
+            while next_edges[vertex] < edges.len() && used[edges[next_edges[vertex]]] {
+                next_edges[vertex] += 1;
+            }
+            if next_edges[vertex] == edges.len() {
+                stack.pop();
+                circuit.extend(arriving_edge);
+            } else {
+                let edge_index = edges[next_edges[vertex]];
+                used[edge_index] = true;
+                let (v1, v2) = self.edges[edge_index].vertices;
+                let forward = v1 == vertex;
+                let destination = if forward { v2 } else { v1 };
+                stack.push((destination, Some((edge_index, forward))));
+            }
+        }
+        circuit.reverse();
+        circuit
+    }
+
+    /// Split given circuit on travel moves and between printed and duplicated edges.
+    /// Return each path with a boolean telling if it is printed.
+    fn split_circuit(&self, circuit: &[(usize, bool)]) -> Vec<(Path, bool)> {
+        // start right after a travel move if any
+        let first = circuit
+            .iter()
+            .position(|&(e, _)| self.edges[e].paths.is_empty())
+            .map_or(0, |p| p + 1);
+        let mut paths = Vec::new();
+        let mut current: Vec<ElementaryPath> = Vec::new();
+        let mut printed = true;
+        for &(edge_index, forward) in circuit[first..].iter().chain(circuit[..first].iter()) {
+            let edge = &self.edges[edge_index];
+            if (edge.paths.is_empty() || edge.duplicated == printed) && !current.is_empty() {
+                paths.push((Path::new(current), printed));
+                current = Vec::new();
+            }
+            printed = !edge.duplicated;
+            if forward {
+                current.extend(edge.paths.iter().cloned());
+            } else {
+                current.extend(edge.paths.iter().rev().map(|p| p.reverse()));
+            }
+        }
+        if !current.is_empty() {
+            paths.push((Path::new(current), printed));
+        }
+        paths
+    }
+}
+
+/// Return root of the set containing given vertex (union-find with path compression).
+fn root(fathers: &mut [usize], vertex: usize) -> usize {
+    let mut current = vertex;
+    while fathers[current] != current {
+        current = fathers[current];
+    }
+    let root = current;
+    let mut current = vertex;
+    while fathers[current] != root {
+        let next = fathers[current];
+        fathers[current] = root;
+        current = next;
+    }
+    root
+}
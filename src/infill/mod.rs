@@ -0,0 +1,5 @@
+//! Infill : filling slices' regions with patterns of toolpaths.
+pub mod pattern;
+pub use self::pattern::Pattern;
+pub mod graph;
+pub use self::graph::InfillGraph;
@@ -0,0 +1,250 @@
+//! Infill patterns : a motif repeated on a rectangular tiling of the plane.
+use boolean::winding;
+use intersections::split_paths;
+use std::f64::consts::PI;
+use std::iter::once;
+use {Arc, Circle, ElementaryPath, HoledPocket, Pocket, Point, PointsHash, Quadrant, Segment};
+
+/// A motif of segments and arcs, repeated on a tiling of the plane by rectangles.
+/// Tiles are aligned on the origin so that the same pattern on successive layers
+/// is at the same place.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// Paths inside the tile [0, width] x [0, height]
+    motif: Vec<ElementaryPath>,
+    /// Tile width
+    width: f64,
+    /// Tile height
+    height: f64,
+}
+
+impl Pattern {
+    /// Create a new pattern from given motif and tile size.
+    /// The motif is made of segments, arcs and circles inside the tile [0, width] x [0, height].
+    pub fn new(motif: Vec<ElementaryPath>, width: f64, height: f64) -> Self {
+        assert!(width > 0.0 && height > 0.0);
+        assert!(!motif
+            .iter()
+            .any(|p| matches!(*p, ElementaryPath::Bezier(_))));
+        Pattern {
+            motif,
+            width,
+            height,
+        }
+    }
+
+    /// Parallel lines separated by given spacing.
+    pub fn rectilinear(spacing: f64) -> Self {
+        Pattern::new(
+            vec![segment((0.0, spacing / 2.0), (spacing, spacing / 2.0))],
+            spacing,
+            spacing,
+        )
+    }
+
+    /// Square grid with lines separated by given spacing.
+    pub fn grid(spacing: f64) -> Self {
+        let half = spacing / 2.0;
+        Pattern::new(
+            vec![
+                segment((0.0, half), (spacing, half)),
+                segment((half, 0.0), (half, spacing)),
+            ],
+            spacing,
+            spacing,
+        )
+    }
+
+    /// Three families of lines at 60 degrees, each separated by given spacing.
+    pub fn triangles(spacing: f64) -> Self {
+        let side = 2.0 * spacing / 3.0f64.sqrt();
+        let height = 2.0 * spacing;
+        Pattern::new(
+            vec![
+                segment((0.0, 0.0), (side, 0.0)),
+                segment((0.0, spacing), (side, spacing)),
+                segment((0.0, 0.0), (side, height)),
+                segment((side, 0.0), (0.0, height)),
+            ],
+            side,
+            height,
+        )
+    }
+
+    /// Hexagons with opposite sides at given spacing.
+    pub fn honeycomb(spacing: f64) -> Self {
+        let side = spacing / 3.0f64.sqrt();
+        let half = spacing / 2.0;
+        Pattern::new(
+            vec![
+                segment((0.0, side / 2.0), (half, 0.0)),
+                segment((half, 0.0), (spacing, side / 2.0)),
+                segment((0.0, side / 2.0), (0.0, 1.5 * side)),
+                segment((0.0, 1.5 * side), (half, 2.0 * side)),
+                segment((half, 2.0 * side), (spacing, 1.5 * side)),
+                segment((half, 2.0 * side), (half, 3.0 * side)),
+            ],
+            spacing,
+            3.0 * side,
+        )
+    }
+
+    /// Eight branches stars in squares of given size, touching their neighbours.
+    /// Each star is a segment rotated eight times around the square's center.
+    pub fn arabic_star(size: f64) -> Self {
+        let center = Point::new(size / 2.0, size / 2.0);
+        // from the middle of the right side to the third next vertex of the octagon
+        let tip = Point::new(size, size / 2.0);
+        let branch = Segment::new(tip, tip.rotate_around(&center, 3.0 * PI / 4.0));
+        Pattern::new(
+            (0..8)
+                .map(|i| {
+                    ElementaryPath::Segment(branch.rotate_around(&center, PI / 4.0 * i as f64))
+                })
+                .collect(),
+            size,
+            size,
+        )
+    }
+
+    /// Return proportion of the plane covered when following the pattern with lines
+    /// of given width (overlaps are not taken into account).
+    pub fn density(&self, line_width: f64) -> f64 {
+        let length: f64 = self.motif.iter().map(|p| p.length()).sum();
+        length * line_width / (self.width * self.height)
+    }
+
+    /// Return same pattern, scaled such that it has given density with given line width.
+    pub fn with_density(&self, line_width: f64, density: f64) -> Self {
+        assert!(density > 0.0);
+        let ratio = self.density(line_width) / density;
+        Pattern {
+            motif: self
+                .motif
+                .iter()
+                .map(|p| transform(p, |point| Point::new(point.x * ratio, point.y * ratio)))
+                .collect(),
+            width: self.width * ratio,
+            height: self.height * ratio,
+        }
+    }
+
+    /// Tile the plane (rotated around the origin by given angle) with our motif,
+    /// enough to cover given quadrant.
+    pub fn tile(
+        &self,
+        quadrant: &Quadrant,
+        angle: f64,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<ElementaryPath> {
+        // find the quadrant in the pattern's frame
+        let (xmin, xmax) = quadrant.limits(0);
+        let (ymin, ymax) = quadrant.limits(1);
+        let origin = Point::origin();
+        let rotated = [(xmin, ymin), (xmax, ymin), (xmax, ymax), (xmin, ymax)]
+            .iter()
+            .map(|&(x, y)| Point::new(x, y).rotate_around(&origin, -angle))
+            .fold(Quadrant::new(), |q, p| q.add(&p));
+        let (xmin, xmax) = rotated.limits(0);
+        let (ymin, ymax) = rotated.limits(1);
+        let columns = (xmin / self.width).floor() as i64..(xmax / self.width).ceil() as i64;
+        let rows = (ymin / self.height).floor() as i64..(ymax / self.height).ceil() as i64;
+        let mut paths = Vec::new();
+        for row in rows {
+            for column in columns.clone() {
+                let (x, y) = (column as f64 * self.width, row as f64 * self.height);
+                paths.extend(self.motif.iter().map(|p| {
+                    transform(p, |point| {
+                        let moved = Point::new(point.x + x, point.y + y);
+                        points_hasher.add(moved.rotate_around(&origin, angle))
+                    })
+                }));
+            }
+        }
+        paths.sort_by(|p1, p2| (p1.start(), p1.end()).cmp(&(p2.start(), p2.end())));
+        paths.dedup_by(|p1, p2| p1.start() == p2.start() && p1.end() == p2.end());
+        paths
+    }
+
+    /// Tile the plane (rotated by given angle) and return all pieces of the pattern
+    /// inside given pocket. Pattern paths are cut on all their intersections.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::infill::Pattern;
+    /// use grouille::{HoledPolygon, PointsHash, Polygon};
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), Vec::new());
+    /// let pocket = square.to_holed_pocket();
+    /// // ten lines of length 10
+    /// let lines = Pattern::rectilinear(1.0).clip(&pocket, 0.0, &mut hasher);
+    /// let length: f64 = lines.iter().map(|l| l.length()).sum();
+    /// assert!((length - 100.0).abs() < 0.00001);
+    /// // twice more with a grid
+    /// let grid = Pattern::grid(1.0).clip(&pocket, 0.0, &mut hasher);
+    /// let length: f64 = grid.iter().map(|l| l.length()).sum();
+    /// assert!((length - 200.0).abs() < 0.00001);
+    /// ```
+    pub fn clip(
+        &self,
+        pocket: &HoledPocket,
+        angle: f64,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<ElementaryPath> {
+        let loops: Vec<Pocket> = once(&pocket.outer_pocket)
+            .chain(pocket.holes.iter())
+            .cloned()
+            .collect();
+        let mut paths = self.tile(&pocket.outer_pocket.quadrant, angle, points_hasher);
+        let pattern_paths = paths.len();
+        paths.extend(loops.iter().flat_map(|l| l.edge().iter().cloned()));
+        split_paths(&paths, points_hasher)
+            .into_iter()
+            .take(pattern_paths)
+            .flat_map(|pieces| pieces.into_iter())
+            .filter(|piece| {
+                let middle = piece.point_at(piece.length() / 2.0);
+                winding(&middle, &loops) > 0
+            })
+            .collect()
+    }
+}
+
+/// Return segment between two given points (as coordinates).
+fn segment(start: (f64, f64), end: (f64, f64)) -> ElementaryPath {
+    ElementaryPath::Segment(Segment::new(
+        Point::new(start.0, start.1),
+        Point::new(end.0, end.1),
+    ))
+}
+
+/// Move all points of given path with given (similarity) transformation.
+fn transform<F: FnMut(Point) -> Point>(
+    path: &ElementaryPath,
+    mut transformation: F,
+) -> ElementaryPath {
+    match *path {
+        ElementaryPath::Segment(ref s) => {
+            ElementaryPath::Segment(Segment::new(transformation(s.start), transformation(s.end)))
+        }
+        ElementaryPath::Arc(ref a) => {
+            let start = transformation(a.start);
+            let center = transformation(a.center);
+            let radius = start.distance_to(&center);
+            ElementaryPath::Arc(Arc::new(
+                start,
+                transformation(a.end),
+                center,
+                radius,
+                a.clockwise,
+            ))
+        }
+        ElementaryPath::Circle(ref c) => {
+            let start = transformation(c.start);
+            let center = transformation(c.center);
+            let radius = start.distance_to(&center);
+            ElementaryPath::Circle(Circle::new(start, center, radius, c.clockwise))
+        }
+        ElementaryPath::Bezier(_) => panic!("patterns cannot contain bezier curves"),
+    }
+}
@@ -37,37 +37,69 @@ pub fn intersect_paths(
     paths: &[ElementaryPath],
     points_hasher: &mut PointsHash,
 ) -> Vec<ElementaryPath> {
+    std::iter::Iterator::flatten(split_paths(paths, points_hasher).into_iter()).collect()
+}
+
+/// Intersect given paths between themselves and return for each path
+/// the subpaths it is cut into.
+pub(crate) fn split_paths(
+    paths: &[ElementaryPath],
+    points_hasher: &mut PointsHash,
+) -> Vec<Vec<ElementaryPath>> {
     let intersections = compute_intersections(paths, points_hasher);
-    let result: Vec<ElementaryPath> = paths
-        .iter()
-        .zip(intersections.into_iter())
-        .flat_map(|(path, path_intersections)| {
-            let i = std::iter::Iterator::flatten(
-                path_intersections
-                    .map(|mut intersections| {
-                        intersections.sort_unstable_by(|i1, i2| {
-                            path.start()
-                                .distance_to(i1)
-                                .partial_cmp(&path.start().distance_to(i2))
-                                .unwrap()
-                        });
-                        intersections
-                    })
-                    .into_iter(),
-            );
+    let mut result: Vec<Vec<ElementaryPath>> = Vec::with_capacity(paths.len());
+    for (path, path_intersections) in paths.iter().zip(intersections) {
+        let i = std::iter::Iterator::flatten(
+            path_intersections
+                .map(|mut intersections| {
+                    intersections.sort_unstable_by(|i1, i2| {
+                        path.distance_from_start(i1)
+                            .partial_cmp(&path.distance_from_start(i2))
+                            .unwrap()
+                    });
+                    intersections
+                })
+                .into_iter(),
+        );
 
+        let points: Vec<Point> = if let ElementaryPath::Circle(_) = *path {
+            // a circle's start is not a real vertex, we loop from first intersection
+            let intersections: Vec<Point> = i.dedup().collect();
+            intersections
+                .first()
+                .map(|first| {
+                    intersections
+                        .iter()
+                        .chain(once(first))
+                        .cloned()
+                        .dedup()
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
             once(*path.start())
                 .chain(i)
                 .chain(once(*path.end()))
                 .dedup()
+                .collect()
+        };
+        if points.len() <= 1 {
+            // a circle with no intersection (or only one) stays whole
+            result.push(vec![points.first().map_or(*path, |&p| path.sub_path(p, p))]);
+            continue;
+        }
+        result.push(
+            points
+                .into_iter()
                 .tuple_windows()
                 .inspect(|(p1, p2)| {
                     if p1.distance_to(p2) < 0.0001 {
                         eprintln!("warning: very short distance between {:?} and {:?}", p1, p2);
                     }
                 })
-                .map(move |(p1, p2)| path.sub_path(p1, p2))
-        })
-        .collect();
+                .map(|(p1, p2)| path.sub_path(p1, p2))
+                .collect(),
+        );
+    }
     result
 }
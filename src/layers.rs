@@ -0,0 +1,146 @@
+//! Heaven and hell : classification of each layer's regions with respect to
+//! the layers just above (the sky) and just below (the earth).
+//!
+//! This decides how regions are filled :
+//! - sky only : nothing below, supports are needed
+//! - earth only : nothing above, full infill (roof)
+//! - sky and earth : partial infill
+//! - nothing : eliminated
+//!
+//! We also find roofs and floors : regions close to a top or bottom surface
+//! which need to be filled solid.
+use boolean::{
+    pockets_difference, pockets_intersection, polygons_difference, polygons_intersection,
+};
+use {HoledPocket, HoledPolygon, PointsHash};
+
+/// Regions of a layer, classified.
+#[derive(Debug)]
+pub struct LayerClassification {
+    /// Regions with material above but none below.
+    pub sky: Vec<HoledPocket>,
+    /// Regions with material below but none above.
+    pub earth: Vec<HoledPocket>,
+    /// Regions with material both above and below.
+    pub sky_and_earth: Vec<HoledPocket>,
+    /// Regions with material neither above nor below.
+    pub nothing: Vec<HoledPocket>,
+}
+
+impl LayerClassification {
+    /// Classify regions of given layer with respect to given layers above and below.
+    pub fn new(
+        layer: &[HoledPocket],
+        above: &[HoledPocket],
+        below: &[HoledPocket],
+        points_hasher: &mut PointsHash,
+    ) -> Self {
+        let covered = pockets_intersection(layer, above, points_hasher);
+        let uncovered = pockets_difference(layer, above, points_hasher);
+        LayerClassification {
+            sky: pockets_difference(&covered, below, points_hasher),
+            earth: pockets_intersection(&uncovered, below, points_hasher),
+            sky_and_earth: pockets_intersection(&covered, below, points_hasher),
+            nothing: pockets_difference(&uncovered, below, points_hasher),
+        }
+    }
+}
+
+/// Classify regions of all given layers (sorted from bottom to top).
+/// There is nothing below the first layer and nothing above the last one.
+///
+/// # Example
+/// ```
+/// use grouille::layers::classify_layers;
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// // a 2x2 square on top of a 4x4 square on top of a 2x2 square
+/// let small = HoledPolygon::new(Polygon::square(0.0, 0.0, 2.0), Vec::new()).to_holed_pocket();
+/// let big = HoledPolygon::new(Polygon::square(-1.0, -1.0, 4.0), Vec::new()).to_holed_pocket();
+/// let layers = vec![vec![small.clone()], vec![big], vec![small]];
+/// let classification = classify_layers(&layers, &mut hasher);
+/// let area = |regions: &[grouille::HoledPocket]| -> f64 {
+///     regions
+///         .iter()
+///         .map(|r| r.outer_pocket.area() + r.holes.iter().map(|h| h.area()).sum::<f64>())
+///         .sum()
+/// };
+/// assert!((area(&classification[0].sky) - 4.0).abs() < 0.00001);
+/// assert!((area(&classification[1].sky_and_earth) - 4.0).abs() < 0.00001);
+/// // a ring around the small squares
+/// assert!((area(&classification[1].nothing) - 12.0).abs() < 0.00001);
+/// assert!((area(&classification[2].earth) - 4.0).abs() < 0.00001);
+/// ```
+pub fn classify_layers(
+    layers: &[Vec<HoledPocket>],
+    points_hasher: &mut PointsHash,
+) -> Vec<LayerClassification> {
+    let nothing = Vec::new();
+    (0..layers.len())
+        .map(|index| {
+            let below = if index == 0 {
+                &nothing
+            } else {
+                &layers[index - 1]
+            };
+            let above = layers.get(index + 1).unwrap_or(&nothing);
+            LayerClassification::new(&layers[index], above, below, points_hasher)
+        })
+        .collect()
+}
+
+/// Regions of a layer, split by infill type.
+#[derive(Debug)]
+pub struct LayerInfill {
+    /// Regions close to a top or bottom surface, to be filled solid.
+    pub solid: Vec<HoledPolygon>,
+    /// True interior regions, to be filled sparsely.
+    pub sparse: Vec<HoledPolygon>,
+}
+
+/// Split all given layers (sorted from bottom to top) into solid and sparse regions.
+/// A region is solid if it is within `solid_layers` layers of a roof or a floor,
+/// that is if one of the `solid_layers` layers above it or below it does not contain it.
+///
+/// # Example
+/// ```
+/// use grouille::layers::roofs_and_floors;
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 4.0), Vec::new());
+/// // a 4x4 square with a 2x2 step from the fourth layer
+/// let step = HoledPolygon::new(Polygon::square(0.0, 0.0, 2.0), Vec::new());
+/// let layers = vec![vec![square.clone()], vec![square.clone()], vec![square], vec![step]];
+/// let infills = roofs_and_floors(&layers, 1, &mut hasher);
+/// assert!(infills[0].sparse.is_empty());
+/// assert!(infills[1].solid.is_empty());
+/// // only the part below the step is not solid
+/// assert!((infills[2].sparse[0].outer_polygon.area() - 4.0).abs() < 0.00001);
+/// assert!((infills[2].solid[0].outer_polygon.area() - 12.0).abs() < 0.00001);
+/// assert!(infills[3].sparse.is_empty());
+/// ```
+pub fn roofs_and_floors(
+    layers: &[Vec<HoledPolygon>],
+    solid_layers: usize,
+    points_hasher: &mut PointsHash,
+) -> Vec<LayerInfill> {
+    layers
+        .iter()
+        .enumerate()
+        .map(|(index, layer)| {
+            let sparse = if index < solid_layers || index + solid_layers >= layers.len() {
+                Vec::new() // surfaces are in range
+            } else {
+                layers[index - solid_layers..=index + solid_layers]
+                    .iter()
+                    .fold(layer.clone(), |interior, neighbour| {
+                        polygons_intersection(&interior, neighbour, points_hasher)
+                    })
+            };
+            LayerInfill {
+                solid: polygons_difference(layer, &sparse, points_hasher),
+                sparse,
+            }
+        })
+        .collect()
+}
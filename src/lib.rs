@@ -6,6 +6,9 @@ extern crate byteorder;
 extern crate itertools;
 extern crate num_traits;
 extern crate streaming_iterator;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 mod quadrant;
 pub use quadrant::Quadrant;
@@ -17,6 +20,8 @@ pub mod segment;
 #[macro_use]
 pub mod tycat;
 pub use segment::Segment;
+pub mod line;
+pub use line::{Line, Ray};
 pub mod overlap;
 mod point;
 pub use point::{Point, Point3, Vector};
@@ -24,16 +29,36 @@ pub mod polygon;
 pub use polygon::Polygon;
 pub mod classifier;
 pub mod holed_polygon;
+pub mod layers;
+pub mod perimeters;
 pub mod slice;
+pub mod supports;
 pub use holed_polygon::HoledPolygon;
 pub mod holed_pocket;
 pub use holed_pocket::HoledPocket;
 pub mod arc;
+pub mod infill;
 pub mod utils;
 pub use arc::Arc;
+pub mod circle;
+pub use circle::Circle;
+pub mod bezier;
+pub use bezier::Bezier;
 pub mod elementary_path;
 pub use elementary_path::ElementaryPath;
+pub mod boolean;
+pub mod fitting;
 pub mod intersections;
+pub mod offset;
+pub mod projection;
+pub use projection::Projection;
 pub mod pocket;
+pub mod pocketing;
 pub use pocket::Pocket;
+pub mod path;
+pub use path::Path;
+pub mod ordering;
+#[cfg(feature = "serde")]
+mod serialization;
 pub(crate) mod shape;
+pub mod travel;
@@ -0,0 +1,363 @@
+//! Provides infinite `Line`s and half infinite `Ray`s.
+use arc::line_circle_intersections;
+use std::cmp::Ordering;
+use tycat::Tycat;
+use utils::is_almost;
+use {Arc, Circle, ElementaryPath, Pocket, Point, Polygon, Quadrant, Segment, Vector};
+
+/// Infinite line going through a point in a given direction.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Line {
+    /// Point on the line
+    pub point: Point,
+    /// Direction
+    pub direction: Vector,
+}
+
+/// Half infinite line starting on a point and going in a given direction.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ray {
+    /// Starting point
+    pub start: Point,
+    /// Direction
+    pub direction: Vector,
+}
+
+impl Line {
+    /// Create a new line going through given point in given direction.
+    pub fn new(point: Point, direction: Vector) -> Line {
+        assert!(direction.x != 0.0 || direction.y != 0.0);
+        Line { point, direction }
+    }
+
+    /// Create the line going through the two given (distinct) points.
+    pub fn through(p1: Point, p2: Point) -> Line {
+        Line::new(p1, p2 - p1)
+    }
+
+    /// Create the line supporting given segment.
+    pub fn supporting(segment: &Segment) -> Line {
+        Line::through(segment.start, segment.end)
+    }
+
+    /// Return the perpendicular bisector of the two given (distinct) points.
+    pub fn bisector(p1: &Point, p2: &Point) -> Line {
+        Line::new(p1.center_with(p2), (p2 - p1).perpendicular_vector())
+    }
+
+    /// Return the line at given angle going through given point.
+    pub fn with_angle(point: Point, angle: f64) -> Line {
+        Line::new(point, Vector::new(angle.cos(), angle.sin()))
+    }
+
+    /// Return point obtained when moving by `parameter` times our direction vector.
+    pub fn point_at(&self, parameter: f64) -> Point {
+        self.point + self.direction * parameter
+    }
+
+    /// Return the parameter of the projection of given point on us.
+    pub fn parameter_of(&self, point: &Point) -> f64 {
+        (point - self.point).scalar_product(&self.direction)
+            / self.direction.scalar_product(&self.direction)
+    }
+
+    /// Return on which side of us given point is.
+    /// Result is positive towards increasing angles (right side in svg orientation),
+    /// negative on the other side and almost zero if we contain the point.
+    pub fn side(&self, point: &Point) -> f64 {
+        self.direction.cross_product(&(point - self.point)) / self.direction.norm()
+    }
+
+    /// Do we contain given point ?
+    pub fn contains(&self, point: &Point) -> bool {
+        is_almost(self.side(point), 0.0)
+    }
+
+    /// Intersect with given line (`None` for almost parallel lines).
+    /// Lines are almost parallel when the sine of their angle is almost zero,
+    /// whatever the length of their direction vectors.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{Line, Point, Vector};
+    /// let horizontal = Line::new(Point::new(0.0, 0.0), Vector::new(0.0001, 0.0));
+    /// let vertical = Line::new(Point::new(1.0, 1.0), Vector::new(0.0, 0.0001));
+    /// let intersection = horizontal.intersection_with_line(&vertical).unwrap();
+    /// assert!(intersection.is_almost(&Point::new(1.0, 0.0)));
+    /// let parallel = Line::new(Point::new(0.0, 1.0), Vector::new(1000.0, 0.0));
+    /// assert!(horizontal.intersection_with_line(&parallel).is_none());
+    /// ```
+    pub fn intersection_with_line(&self, other: &Line) -> Option<Point> {
+        let denominator = self.direction.cross_product(&other.direction);
+        let sine = denominator / (self.direction.norm() * other.direction.norm());
+        if is_almost(sine, 0.0) {
+            None
+        } else {
+            let alpha = (other.point - self.point).cross_product(&other.direction) / denominator;
+            Some(self.point_at(alpha))
+        }
+    }
+
+    /// Intersect with given segment (`None` for almost parallel segments).
+    pub fn intersection_with_segment(&self, segment: &Segment) -> Option<Point> {
+        self.intersection_with_line(&Line::supporting(segment))
+            .filter(|p| segment.contains(p))
+    }
+
+    /// Iterate on all points obtained when intersecting with given arc.
+    pub fn intersections_with_arc<'a>(&self, arc: &'a Arc) -> impl Iterator<Item = Point> + 'a {
+        line_circle_intersections(self, &arc.center, arc.radius)
+            .filter(move |p| arc.contains_circle_point(p))
+    }
+
+    /// Iterate on all points obtained when intersecting with given circle.
+    pub fn intersections_with_circle(&self, circle: &Circle) -> impl Iterator<Item = Point> {
+        line_circle_intersections(self, &circle.center, circle.radius)
+    }
+
+    /// Return all points obtained when intersecting with given elementary path.
+    pub fn intersections_with_path(&self, path: &ElementaryPath) -> Vec<Point> {
+        match *path {
+            ElementaryPath::Segment(ref s) => {
+                self.intersection_with_segment(s).into_iter().collect()
+            }
+            ElementaryPath::Arc(ref a) => self.intersections_with_arc(a).collect(),
+            ElementaryPath::Circle(ref c) => self.intersections_with_circle(c).collect(),
+            ElementaryPath::Bezier(ref b) => self
+                .clip(&b.quadrant())
+                .map(|s| b.intersections_with_segment(&s))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Return the segment of us covering given quadrant's projection (if any).
+    fn clip(&self, quadrant: &Quadrant) -> Option<Segment> {
+        let (xmin, xmax) = quadrant.limits(0);
+        let (ymin, ymax) = quadrant.limits(1);
+        let corners = [
+            Point::new(xmin, ymin),
+            Point::new(xmin, ymax),
+            Point::new(xmax, ymin),
+            Point::new(xmax, ymax),
+        ];
+        let sides = corners.iter().map(|c| self.side(c));
+        if sides.clone().all(|s| s > 0.0) || sides.clone().all(|s| s < 0.0) {
+            return None;
+        }
+        let parameters = corners.iter().map(|c| self.parameter_of(c));
+        let min = parameters.clone().fold(f64::INFINITY, f64::min);
+        let max = parameters.fold(f64::NEG_INFINITY, f64::max);
+        Some(Segment::new(self.point_at(min), self.point_at(max)))
+    }
+
+    /// Return all points where we cross given polygon's edge, sorted along our direction.
+    /// Points where we only touch the polygon without crossing it are not returned
+    /// which ensures we alternate between entering and leaving the polygon.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{Line, Point, Polygon};
+    /// let square = Polygon::square(0.0, 0.0, 2.0);
+    /// let diagonal = Line::through(Point::new(0.0, 0.0), Point::new(1.0, 1.0));
+    /// let points = diagonal.intersections_with_polygon(&square);
+    /// assert_eq!(points.len(), 2);
+    /// assert!(points[1].is_almost(&Point::new(2.0, 2.0)));
+    /// let touching = Line::through(Point::new(0.0, 4.0), Point::new(4.0, 0.0));
+    /// assert!(touching.intersections_with_polygon(&square).is_empty());
+    /// ```
+    pub fn intersections_with_polygon(&self, polygon: &Polygon) -> Vec<Point> {
+        let points = polygon.points();
+        let sides: Vec<Ordering> = points.iter().map(|p| self.side_of(p)).collect();
+        let count = points.len();
+        let mut intersections = Vec::new();
+        for index in 0..count {
+            let next = (index + 1) % count;
+            match (sides[index], sides[next]) {
+                (Ordering::Equal, _) => {
+                    // we cross on the point if non aligned neighbours are on each side
+                    let previous_side = (1..count)
+                        .map(|i| sides[(index + count - i) % count])
+                        .find(|&s| s != Ordering::Equal);
+                    let next_side = (1..count)
+                        .map(|i| sides[(index + i) % count])
+                        .find(|&s| s != Ordering::Equal);
+                    // on aligned points, only keep the last one
+                    if sides[next] != Ordering::Equal && previous_side != next_side {
+                        intersections.push(self.point_at(self.parameter_of(&points[index])));
+                    }
+                }
+                (_, Ordering::Equal) => (),
+                (s1, s2) if s1 != s2 => intersections.extend(
+                    self.intersection_with_line(&Line::through(points[index], points[next])),
+                ),
+                _ => (),
+            }
+        }
+        self.sort_along(&mut intersections);
+        intersections
+    }
+
+    /// Return all points where we cross given pocket's edge, sorted along our direction.
+    /// Just like for polygons, points where we only touch the edge are not returned.
+    pub fn intersections_with_pocket(&self, pocket: &Pocket) -> Vec<Point> {
+        self.crossings_with_pocket(pocket)
+            .into_iter()
+            .map(|(point, _)| point)
+            .collect()
+    }
+
+    /// Return all points where we cross given pocket's edge, sorted along our direction,
+    /// together with the crossing direction :
+    /// +1 if the edge goes from our left side to our right side (towards increasing angles)
+    /// and -1 otherwise.
+    pub(crate) fn crossings_with_pocket(&self, pocket: &Pocket) -> Vec<(Point, i32)> {
+        let edge = pocket.edge();
+        let count = edge.len();
+        let mut intersections = Vec::new();
+        for (index, path) in edge.iter().enumerate() {
+            let previous_path = &edge[(index + count - 1) % count];
+            for point in self.intersections_with_path(path) {
+                if point.is_almost(path.end()) && count > 1 {
+                    continue; // handled by next path
+                }
+                // look for sides around the intersection, further away if we cannot decide
+                let (before, after) = if point.is_almost(path.start()) {
+                    let previous_length = previous_path.length();
+                    (
+                        self.first_side(&[
+                            previous_path.point_at(previous_length - CROSSING_CHECK),
+                            previous_path.point_at(previous_length / 2.0),
+                            *previous_path.start(),
+                        ]),
+                        self.first_side(&[
+                            path.point_at(CROSSING_CHECK),
+                            path.point_at(path.length() / 2.0),
+                            *path.end(),
+                        ]),
+                    )
+                } else {
+                    let distance = path.distance_from_start(&point);
+                    (
+                        self.first_side(&[
+                            path.point_at((distance - CROSSING_CHECK).max(0.0)),
+                            *path.start(),
+                        ]),
+                        self.first_side(&[
+                            path.point_at((distance + CROSSING_CHECK).min(path.length())),
+                            *path.end(),
+                        ]),
+                    )
+                };
+                if before != after {
+                    let direction = if before < after { 1 } else { -1 };
+                    intersections.push((point, direction));
+                }
+            }
+        }
+        intersections.sort_by(|(p1, _), (p2, _)| {
+            self.parameter_of(p1)
+                .partial_cmp(&self.parameter_of(p2))
+                .unwrap()
+        });
+        intersections
+    }
+
+    /// Return on which side of us given point is (`Equal` if we contain it).
+    fn side_of(&self, point: &Point) -> Ordering {
+        let side = self.side(point);
+        if is_almost(side, 0.0) {
+            Ordering::Equal
+        } else {
+            side.partial_cmp(&0.0).unwrap()
+        }
+    }
+
+    /// Return side of first given point not on us.
+    /// Points are considered on us only if extremely close since edges
+    /// tangent to us are very close to us around the tangency point.
+    fn first_side(&self, points: &[Point]) -> Ordering {
+        points
+            .iter()
+            .map(|p| self.side(p))
+            .find(|s| s.abs() > TANGENCY_PRECISION)
+            .map_or(Ordering::Equal, |s| s.partial_cmp(&0.0).unwrap())
+    }
+
+    /// Sort given points (on us) following our direction.
+    fn sort_along(&self, points: &mut [Point]) {
+        points.sort_by(|p1, p2| {
+            self.parameter_of(p1)
+                .partial_cmp(&self.parameter_of(p2))
+                .unwrap()
+        });
+    }
+}
+
+/// Distance travelled along paths around an intersection to figure out
+/// if we cross or just touch them.
+const CROSSING_CHECK: f64 = 0.001;
+
+/// Under this distance points are considered on the line when checking crossings.
+const TANGENCY_PRECISION: f64 = 0.000_000_001;
+
+impl Ray {
+    /// Create a new ray starting at given point in given direction.
+    pub fn new(start: Point, direction: Vector) -> Ray {
+        assert!(direction.x != 0.0 || direction.y != 0.0);
+        Ray { start, direction }
+    }
+
+    /// Return the line supporting us.
+    pub fn line(&self) -> Line {
+        Line::new(self.start, self.direction)
+    }
+
+    /// Do we contain given point ?
+    pub fn contains(&self, point: &Point) -> bool {
+        let line = self.line();
+        line.contains(point) && (line.parameter_of(point) >= 0.0 || point.is_almost(&self.start))
+    }
+
+    /// Intersect with given segment (`None` for almost parallel segments).
+    pub fn intersection_with_segment(&self, segment: &Segment) -> Option<Point> {
+        self.line()
+            .intersection_with_segment(segment)
+            .filter(|p| self.contains(p))
+    }
+
+    /// Return all points obtained when intersecting with given arc.
+    pub fn intersections_with_arc(&self, arc: &Arc) -> Vec<Point> {
+        self.line()
+            .intersections_with_arc(arc)
+            .filter(|p| self.contains(p))
+            .collect()
+    }
+
+    /// Return all points where we cross given polygon's edge, sorted by distance from start.
+    pub fn intersections_with_polygon(&self, polygon: &Polygon) -> Vec<Point> {
+        let mut points = self.line().intersections_with_polygon(polygon);
+        points.retain(|p| self.contains(p));
+        points
+    }
+
+    /// Return all points where we cross given pocket's edge, sorted by distance from start.
+    pub fn intersections_with_pocket(&self, pocket: &Pocket) -> Vec<Point> {
+        let mut points = self.line().intersections_with_pocket(pocket);
+        points.retain(|p| self.contains(p));
+        points
+    }
+
+    /// Return the winding number of given pocket around our start,
+    /// counted positively for clockwise pockets (positive area).
+    /// Unlike parity tests this is correct for self intersecting pockets.
+    pub(crate) fn winding_number(&self, pocket: &Pocket) -> i32 {
+        self.line()
+            .crossings_with_pocket(pocket)
+            .into_iter()
+            .filter(|(p, _)| self.contains(p))
+            .map(|(_, direction)| direction)
+            .sum::<i32>()
+    }
+}
@@ -0,0 +1,43 @@
+//! Tools shared by all offsetting algorithms.
+//!
+//! Each offset starts by building raw loops : all edges are displaced and
+//! joined by arcs around original vertices. The offset region is then the
+//! region of positive winding number of these raw loops.
+use boolean::{loops_operation, Operation};
+use holed_pocket::build_holed_pockets;
+use {ElementaryPath, HoledPocket, Pocket, PointsHash};
+
+/// How displaced edges are connected around convex corners when growing shapes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Join {
+    /// Arc around the corner (exact offset)
+    Round,
+    /// Prolongate edges until they meet, unless the meeting point is further
+    /// from the corner than given ratio times the radius (in which case we square).
+    Miter(f64),
+    /// Cut the corner at radius distance from it
+    Square,
+}
+
+/// Return holed pockets delimiting the region of positive winding of given raw loops.
+pub(crate) fn winding_region(
+    raw_loops: Vec<Vec<ElementaryPath>>,
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    let loops: Vec<Pocket> = raw_loops
+        .into_iter()
+        .filter(|edge| !edge.is_empty())
+        .map(Pocket::new)
+        .collect();
+    let pockets = loops_operation(&loops, &[], Operation::Union, points_hasher)
+        .into_iter()
+        .map(|edge| {
+            let mut pocket = Pocket::new(edge);
+            if !pocket.is_oriented_clockwise() {
+                pocket.reverse();
+            }
+            pocket
+        })
+        .collect();
+    build_holed_pockets(pockets)
+}
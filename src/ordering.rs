@@ -0,0 +1,147 @@
+//! Ordering of toolpaths, minimizing non-cutting travel between them.
+//!
+//! We start with a nearest neighbour tour and improve it with 2-opt moves.
+//! Closed paths can start anywhere and open paths can be followed in both directions.
+use {Path, Point};
+
+/// Maximal number of improvement passes.
+const MAX_PASSES: usize = 100;
+
+/// Minimal travel decrease for a move to be considered an improvement.
+const IMPROVEMENT_THRESHOLD: f64 = 0.000001;
+
+/// Order given paths, starting from given point, such that travel between them is short.
+/// Open paths might get reversed and closed paths might start elsewhere (but keep their
+/// direction).
+/// We return ordered paths and total travel distance.
+///
+/// # Example
+/// ```
+/// use grouille::ordering::order_paths;
+/// use grouille::{ElementaryPath, Path, Point, Polygon, Segment};
+/// let segment = |x1, x2| {
+///     let segment = Segment::new(Point::new(x1, 0.0), Point::new(x2, 0.0));
+///     Path::new(vec![ElementaryPath::Segment(segment)])
+/// };
+/// let square = Polygon::square(4.0, -0.5, 1.0).to_pocket().to_path();
+/// let paths = vec![square, segment(3.0, 2.0), segment(0.0, 1.0)];
+/// let (ordered, travel) = order_paths(paths, &Point::new(0.0, 0.0));
+/// assert!((travel - 2.0).abs() < 0.00001);
+/// assert_eq!(*ordered[1].start(), Point::new(2.0, 0.0));
+/// assert!(ordered[2].start().distance_to(&Point::new(4.0, 0.0)) < 0.00001);
+/// ```
+pub fn order_paths(paths: Vec<Path>, start: &Point) -> (Vec<Path>, f64) {
+    let mut ordered = nearest_neighbours(paths, start);
+    for _ in 0..MAX_PASSES {
+        let two_opt_improved = two_opt(&mut ordered, start);
+        let restart_improved = move_starts(&mut ordered, start);
+        if !two_opt_improved && !restart_improved {
+            break;
+        }
+    }
+    let travel = travel_distance(&ordered, start);
+    (ordered, travel)
+}
+
+/// Return total travel distance needed to follow given paths in order, from given point.
+pub fn travel_distance(paths: &[Path], start: &Point) -> f64 {
+    paths
+        .iter()
+        .fold((0.0, start), |(travel, position), path| {
+            (travel + position.distance_to(path.start()), path.end())
+        })
+        .0
+}
+
+/// Greedily build a tour, always going to the closest remaining path.
+fn nearest_neighbours(mut paths: Vec<Path>, start: &Point) -> Vec<Path> {
+    let mut ordered = Vec::with_capacity(paths.len());
+    let mut position = *start;
+    while !paths.is_empty() {
+        let closest = (0..paths.len())
+            .min_by(|&i1, &i2| {
+                entry_distance(&paths[i1], &position)
+                    .partial_cmp(&entry_distance(&paths[i2], &position))
+                    .unwrap()
+            })
+            .unwrap();
+        let path = paths.swap_remove(closest);
+        let entered = if path.is_closed() {
+            path.starting_at(&position)
+        } else if position.distance_to(path.end()) < position.distance_to(path.start()) {
+            path.reverse()
+        } else {
+            path
+        };
+        position = *entered.end();
+        ordered.push(entered);
+    }
+    ordered
+}
+
+/// Return distance between given point and the closest place where we can start given path.
+fn entry_distance(path: &Path, point: &Point) -> f64 {
+    if path.is_closed() {
+        path.projection(point).distance
+    } else {
+        point
+            .distance_to(path.start())
+            .min(point.distance_to(path.end()))
+    }
+}
+
+/// Apply all 2-opt moves decreasing travel : reverse the order of a sub-sequence of paths
+/// (reversing open paths themselves). Return if we improved anything.
+fn two_opt(paths: &mut [Path], start: &Point) -> bool {
+    let mut improved = false;
+    for first in 0..paths.len() {
+        for last in first..paths.len() {
+            let before = if first == 0 {
+                *start
+            } else {
+                *paths[first - 1].end()
+            };
+            let (old_travel, new_travel) = {
+                let after = paths.get(last + 1).map(|p| p.start());
+                let travel_out = |point: &Point| after.map_or(0.0, |a| point.distance_to(a));
+                (
+                    before.distance_to(paths[first].start()) + travel_out(paths[last].end()),
+                    before.distance_to(paths[last].end()) + travel_out(paths[first].start()),
+                )
+            };
+            if new_travel < old_travel - IMPROVEMENT_THRESHOLD {
+                let reversed = &mut paths[first..=last];
+                reversed.reverse();
+                for path in reversed.iter_mut().filter(|p| !p.is_closed()) {
+                    *path = path.reverse();
+                }
+                improved = true;
+            }
+        }
+    }
+    improved
+}
+
+/// Move start of each closed path to the point closest from the previous path's end,
+/// if it decreases travel. Return if we improved anything.
+fn move_starts(paths: &mut [Path], start: &Point) -> bool {
+    let mut improved = false;
+    let mut position = *start;
+    for index in 0..paths.len() {
+        if paths[index].is_closed() {
+            let moved = paths[index].starting_at(&position);
+            let travel = |path: &Path| {
+                position.distance_to(path.start())
+                    + paths
+                        .get(index + 1)
+                        .map_or(0.0, |next| path.end().distance_to(next.start()))
+            };
+            if travel(&moved) < travel(&paths[index]) - IMPROVEMENT_THRESHOLD {
+                paths[index] = moved;
+                improved = true;
+            }
+        }
+        position = *paths[index].end();
+    }
+    improved
+}
@@ -45,7 +45,7 @@ pub fn remove_segments_overlaps(
     for path in paths {
         match path {
             ElementaryPath::Segment(s) => elementary_segments.push(s),
-            a @ ElementaryPath::Arc(_) => remaining_paths.push(a),
+            other => remaining_paths.push(other),
         }
     }
     unimplemented!("TODO: we cannot call remove_overlaps here");
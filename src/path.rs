@@ -0,0 +1,200 @@
+//! Provide a `Path` type for open (or closed) toolpaths.
+use projection::closest_projection;
+use std::f64::consts::PI;
+use tycat::Tycat;
+use utils::is_almost;
+use {ElementaryPath, Point, Projection, Quadrant};
+
+/// Sequence of consecutive `ElementaryPath`s.
+/// Unlike `Pocket`s, paths do not need to be closed.
+#[derive(Debug, Clone)]
+pub struct Path {
+    /// All paths, one after the other.
+    pub(crate) paths: Vec<ElementaryPath>,
+    /// Quadrant containing us.
+    pub(crate) quadrant: Quadrant,
+}
+
+impl Path {
+    /// Build a new `Path` from given consecutive elementary paths.
+    /// pre-condition: at least one elementary path.
+    pub fn new(paths: Vec<ElementaryPath>) -> Self {
+        assert!(!paths.is_empty());
+        debug_assert!(paths.windows(2).all(|p| p[0].end().is_almost(p[1].start())));
+        let mut quadrant = Quadrant::new();
+        for path in &paths {
+            quadrant.update(&path.quadrant());
+        }
+        Path { paths, quadrant }
+    }
+
+    /// Return all elementary paths as a slice (read only).
+    pub fn paths(&self) -> &[ElementaryPath] {
+        &self.paths
+    }
+
+    /// Return starting point.
+    pub fn start(&self) -> &Point {
+        self.paths.first().unwrap().start()
+    }
+
+    /// Return ending point.
+    pub fn end(&self) -> &Point {
+        self.paths.last().unwrap().end()
+    }
+
+    /// Do we end where we start ?
+    pub fn is_closed(&self) -> bool {
+        self.start() == self.end()
+    }
+
+    /// Return our total length.
+    pub fn length(&self) -> f64 {
+        self.paths.iter().map(|p| p.length()).sum()
+    }
+
+    /// Return path in opposite direction.
+    pub fn reverse(&self) -> Path {
+        Path {
+            paths: self.paths.iter().rev().map(|p| p.reverse()).collect(),
+            quadrant: self.quadrant,
+        }
+    }
+
+    /// Return index of elementary path containing point at given distance from start
+    /// together with remaining distance inside it.
+    fn locate(&self, mut distance: f64) -> (usize, f64) {
+        for (index, path) in self.paths.iter().enumerate() {
+            let length = path.length();
+            if distance <= length {
+                return (index, distance);
+            }
+            distance -= length;
+        }
+        let last = self.paths.len() - 1;
+        (last, self.paths[last].length())
+    }
+
+    /// Return point at given distance from start (travelling along ourselves).
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{ElementaryPath, Path, Point, Segment};
+    /// let path = Path::new(vec![
+    ///     ElementaryPath::Segment(Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0))),
+    ///     ElementaryPath::Segment(Segment::new(Point::new(1.0, 0.0), Point::new(1.0, 2.0))),
+    /// ]);
+    /// assert!(path.point_at(2.0).is_almost(&Point::new(1.0, 1.0)));
+    /// let (start, end) = path.split_at(0.5);
+    /// assert!((start.length() - 0.5).abs() < 0.000001);
+    /// assert!((end.length() - 2.5).abs() < 0.000001);
+    /// ```
+    pub fn point_at(&self, distance: f64) -> Point {
+        let (index, remaining_distance) = self.locate(distance);
+        self.paths[index].point_at(remaining_distance)
+    }
+
+    /// Split ourselves in two paths at given distance from start.
+    /// pre-condition: distance is strictly between 0 and our length and not almost
+    /// at one of our ends (both paths need to be non empty).
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{ElementaryPath, Path, Point, Segment};
+    /// let path = Path::new(vec![
+    ///     ElementaryPath::Segment(Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0))),
+    ///     ElementaryPath::Segment(Segment::new(Point::new(1.0, 0.0), Point::new(1.0, 2.0))),
+    /// ]);
+    /// // splitting at a junction is fine
+    /// let (start, end) = path.split_at(1.0);
+    /// assert_eq!(start.paths().len(), 1);
+    /// assert_eq!(end.paths().len(), 1);
+    /// ```
+    ///
+    /// Splitting almost on an end is not :
+    /// ```should_panic
+    /// # use grouille::{ElementaryPath, Path, Point, Segment};
+    /// # let path = Path::new(vec![
+    /// #     ElementaryPath::Segment(Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0))),
+    /// #     ElementaryPath::Segment(Segment::new(Point::new(1.0, 0.0), Point::new(1.0, 2.0))),
+    /// # ]);
+    /// path.split_at(1e-8);
+    /// ```
+    ///
+    /// ```should_panic
+    /// # use grouille::{ElementaryPath, Path, Point, Segment};
+    /// # let path = Path::new(vec![
+    /// #     ElementaryPath::Segment(Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0))),
+    /// #     ElementaryPath::Segment(Segment::new(Point::new(1.0, 0.0), Point::new(1.0, 2.0))),
+    /// # ]);
+    /// path.split_at(3.0 - 1e-8);
+    /// ```
+    pub fn split_at(&self, distance: f64) -> (Path, Path) {
+        let length = self.length();
+        assert!(
+            distance > 0.0
+                && distance < length
+                && !is_almost(distance, 0.0)
+                && !is_almost(distance, length)
+        );
+        let (index, remaining_distance) = self.locate(distance);
+        let path = &self.paths[index];
+        let mut first_paths = self.paths[..index].to_vec();
+        let mut second_paths = Vec::with_capacity(self.paths.len() - index);
+        if is_almost(remaining_distance, path.length()) {
+            first_paths.push(*path);
+        } else if is_almost(remaining_distance, 0.0) {
+            second_paths.push(*path);
+        } else {
+            let split_point = path.point_at(remaining_distance);
+            first_paths.push(path.sub_path(*path.start(), split_point));
+            second_paths.push(path.sub_path(split_point, *path.end()));
+        }
+        second_paths.extend(self.paths[index + 1..].iter().cloned());
+        (Path::new(first_paths), Path::new(second_paths))
+    }
+
+    /// Add given path at our end.
+    /// pre-condition: given path starts where we end.
+    pub fn append(&mut self, other: Path) {
+        assert!(self.end().is_almost(other.start()));
+        self.quadrant.update(&other.quadrant);
+        self.paths.extend(other.paths);
+    }
+
+    /// Return same closed path, but starting (and ending) at our closest point from given one.
+    pub fn starting_at(&self, point: &Point) -> Path {
+        assert!(self.is_closed());
+        let projection = self.projection(point);
+        let distance = self.paths[..projection.index]
+            .iter()
+            .map(|p| p.length())
+            .sum::<f64>()
+            + projection.parameter;
+        if is_almost(distance, 0.0) || is_almost(distance, self.length()) {
+            self.clone()
+        } else {
+            let (first, mut second) = self.split_at(distance);
+            second.append(first);
+            second
+        }
+    }
+
+    /// Return closest point on ourselves from given point.
+    /// Returned index is the index of the elementary path containing it.
+    pub fn projection(&self, point: &Point) -> Projection {
+        closest_projection(self.paths.iter().map(|p| p.projection(point))).unwrap()
+    }
+
+    /// Is the direction change at each junction between elementary paths
+    /// smaller than given angle ?
+    pub fn is_tangent_continuous(&self, max_angle: f64) -> bool {
+        self.paths.windows(2).all(|p| {
+            // end angles point backwards
+            let incoming = p[0].end_angles().0.value() - PI;
+            let outgoing = p[1].start_angles().0.value();
+            let difference = (outgoing - incoming).rem_euclid(2.0 * PI);
+            difference.min(2.0 * PI - difference) <= max_angle
+        })
+    }
+}
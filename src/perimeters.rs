@@ -0,0 +1,121 @@
+//! Perimeters (shells) for 3D printing : loops following the walls of each layer.
+//!
+//! The outer wall is offset by half an extrusion width and the next ones by full widths.
+//! Parts too thin for a shell are filled by single extrusions and the remaining interior
+//! is left for infill.
+use boolean::{pockets_difference, pockets_union};
+use pocketing::zigzag;
+use std::iter::once;
+use {HoledPocket, HoledPolygon, Path, PointsHash};
+
+/// Perimeters of a region.
+#[derive(Debug)]
+pub struct Perimeters {
+    /// All shells, from the outermost one. The loops to print are the edges of the pockets.
+    pub shells: Vec<Vec<HoledPocket>>,
+    /// Single extrusions filling parts too thin for a shell.
+    pub thin_walls: Vec<Path>,
+    /// Interior left inside the last shell, to be filled by infill.
+    pub infill: Vec<HoledPocket>,
+}
+
+impl Perimeters {
+    /// Compute given number of shells of given extrusion width inside given region.
+    ///
+    /// Parts of the region not covered by the shells (nor left for the infill) are thin walls
+    /// if at least a quarter of a width thick. Each of them is filled by lines along its
+    /// longest edge, which gives a single line in its middle when thinner than a width.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::perimeters::Perimeters;
+    /// use grouille::{HoledPolygon, Point, PointsHash, Polygon};
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), Vec::new());
+    /// let perimeters = Perimeters::new(&square, 2, 0.5, &mut hasher);
+    /// assert_eq!(perimeters.shells.len(), 2);
+    /// assert!((perimeters.shells[0][0].outer_pocket.area() - 9.5 * 9.5).abs() < 0.00001);
+    /// assert!((perimeters.shells[1][0].outer_pocket.area() - 8.5 * 8.5).abs() < 0.00001);
+    /// assert!((perimeters.infill[0].outer_pocket.area() - 64.0).abs() < 0.00001);
+    /// assert!(perimeters.thin_walls.is_empty());
+    /// // walls of length 10
+    /// let wall = |thickness| {
+    ///     HoledPolygon::new(Polygon::new(vec![
+    ///         Point::new(0.0, 0.0),
+    ///         Point::new(10.0, 0.0),
+    ///         Point::new(10.0, thickness),
+    ///         Point::new(0.0, thickness),
+    ///     ]), Vec::new())
+    /// };
+    /// // thinner than the extrusion width : a single line
+    /// let perimeters = Perimeters::new(&wall(0.4), 2, 0.5, &mut hasher);
+    /// assert!(perimeters.shells.is_empty());
+    /// assert_eq!(perimeters.thin_walls.len(), 1);
+    /// assert!((perimeters.thin_walls[0].length() - 10.0).abs() < 0.00001);
+    /// // one shell and a line inside it
+    /// let perimeters = Perimeters::new(&wall(1.3), 2, 0.5, &mut hasher);
+    /// assert_eq!(perimeters.shells.len(), 1);
+    /// assert_eq!(perimeters.thin_walls.len(), 1);
+    /// assert!((perimeters.thin_walls[0].length() - 9.0).abs() < 0.00001);
+    /// ```
+    pub fn new(
+        region: &HoledPolygon,
+        shells_number: usize,
+        extrusion_width: f64,
+        points_hasher: &mut PointsHash,
+    ) -> Self {
+        assert!(shells_number > 0 && extrusion_width > 0.0);
+        let shells: Vec<Vec<HoledPocket>> = (0..shells_number)
+            .map(|index| {
+                let distance = extrusion_width * (index as f64 + 0.5);
+                region.offset(distance, points_hasher)
+            })
+            .take_while(|shell| !shell.is_empty())
+            .collect();
+        let infill = if shells.len() == shells_number {
+            region.offset(extrusion_width * shells_number as f64, points_hasher)
+        } else {
+            Vec::new()
+        };
+
+        // find what is neither printed by the shells nor left for the infill
+        let mut printed = infill.clone();
+        for pocket in shells.iter().flat_map(|shell| shell.iter()) {
+            let outside = pocket.outward_offset(extrusion_width / 2.0, points_hasher);
+            let inside = pocket.offset(extrusion_width / 2.0, points_hasher);
+            printed.extend(pockets_difference(&outside, &inside, points_hasher));
+        }
+        let printed = pockets_union(&printed, &[], points_hasher);
+        let gaps = pockets_difference(&[region.to_holed_pocket()], &printed, points_hasher);
+        let thin_walls = gaps
+            .iter()
+            .filter(|gap| !gap.offset(extrusion_width / 8.0, points_hasher).is_empty())
+            .flat_map(|gap| {
+                let longest_edge = once(&gap.outer_pocket)
+                    .chain(gap.holes.iter())
+                    .flat_map(|pocket| pocket.edge().iter())
+                    .max_by(|e1, e2| e1.length().partial_cmp(&e2.length()).unwrap())
+                    .unwrap();
+                let angle = (*longest_edge.end() - *longest_edge.start()).angle();
+                zigzag(gap, angle, extrusion_width)
+            })
+            .collect();
+
+        Perimeters {
+            shells,
+            thin_walls,
+            infill,
+        }
+    }
+
+    /// Return all paths to print, outer shell first (shells loops, then thin walls).
+    pub fn toolpaths(&self) -> Vec<Path> {
+        self.shells
+            .iter()
+            .flat_map(|shell| shell.iter())
+            .flat_map(|pocket| once(&pocket.outer_pocket).chain(pocket.holes.iter()))
+            .map(|pocket| pocket.to_path())
+            .chain(self.thin_walls.iter().cloned())
+            .collect()
+    }
+}
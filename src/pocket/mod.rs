@@ -1,10 +1,14 @@
 //! Provide a `Pocket` type.
+use projection::closest_projection;
 use tycat::Tycat;
-use {ElementaryPath, Point, Polygon, Quadrant};
+use utils::is_almost;
+use {
+    Arc, Circle, ElementaryPath, Path, Point, PointsHash, Polygon, Projection, Quadrant, Segment,
+};
 pub mod pocket_builder;
 
 /// `Polygon` equivalent, but also allowing arcs.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Pocket {
     /// All paths forming the pocket, one after the other.
     pub(crate) edge: Vec<ElementaryPath>,
@@ -22,13 +26,165 @@ impl Pocket {
         Pocket { edge, quadrant }
     }
 
+    /// Return all paths forming our edge as a slice (read only).
+    pub fn edge(&self) -> &[ElementaryPath] {
+        &self.edge
+    }
+
+    /// Return our edge as a closed `Path`.
+    pub fn to_path(&self) -> Path {
+        Path::new(self.edge.clone())
+    }
+
+    /// Return closest point on our edge from given point.
+    /// Returned index is the index of the elementary path containing it.
+    pub fn projection(&self, point: &Point) -> Projection {
+        closest_projection(self.edge.iter().map(|p| p.projection(point))).expect("empty pocket")
+    }
+
     /// Iterate on all our points.
     pub fn points(&self) -> impl Iterator<Item = &Point> {
         self.edge.iter().map(|p| p.start())
     }
 
+    /// Return the (signed) area enclosed by our edge.
+    /// Positive if we are oriented clockwise.
+    pub fn area(&self) -> f64 {
+        self.edge.iter().map(|p| p.signed_area()).sum()
+    }
+
+    /// Convert ourselves to a polygon such that no point of our edge
+    /// is further than given distance from the polygon's edge.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{Circle, ElementaryPath, Pocket, Point};
+    /// let circle = Circle::new(Point::new(1.0, 0.0), Point::origin(), 1.0, true);
+    /// let pocket = Pocket::new(vec![ElementaryPath::Circle(circle)]);
+    /// let polygon = pocket.to_polygon(0.001);
+    /// assert!((pocket.area() - std::f64::consts::PI).abs() < 0.000001);
+    /// assert!((pocket.area() - polygon.area()).abs() < 0.01);
+    /// ```
+    pub fn to_polygon(&self, max_chord_error: f64) -> Polygon {
+        Polygon::new(
+            self.edge
+                .iter()
+                .flat_map(|p| {
+                    let mut points = p.flatten(max_chord_error);
+                    points.pop(); // next path's start
+                    points
+                })
+                .collect(),
+        )
+    }
+
+    /// Reverse pocket orientation in place.
+    pub fn reverse(&mut self) {
+        self.edge = self.edge.iter().rev().map(|p| p.reverse()).collect();
+    }
+
+    /// Returns if pocket is oriented clockwise (with respect to svg
+    /// orientation)
+    pub fn is_oriented_clockwise(&self) -> bool {
+        self.area() > 0.0
+    }
+
+    /// Return vector of elementary paths obtained when displacing all paths internally
+    /// (on their right side) by given radius.
+    /// Arcs become concentric arcs (vanishing, or inverted when the radius exceeds theirs)
+    /// and all paths are joined by arcs around their common points.
+    /// Bezier curves are flattened beforehand.
+    pub(crate) fn inner_paths(
+        &self,
+        radius: f64,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<ElementaryPath> {
+        let edge: Vec<ElementaryPath> = self
+            .edge
+            .iter()
+            .flat_map(|path| match *path {
+                ElementaryPath::Bezier(_) => path
+                    .flatten(BEZIER_OFFSET_PRECISION)
+                    .windows(2)
+                    .map(|p| ElementaryPath::Segment(Segment::new(p[0], p[1])))
+                    .collect(),
+                _ => vec![*path],
+            })
+            .collect();
+        let displaced: Vec<(Point, Point, Option<ElementaryPath>)> = edge
+            .iter()
+            .map(|path| inner_parallel_path(path, radius, points_hasher))
+            .collect();
+        let mut paths = Vec::with_capacity(2 * edge.len());
+        for (index, &(_, end, path)) in displaced.iter().enumerate() {
+            paths.extend(path);
+            let next_start = displaced[(index + 1) % displaced.len()].0;
+            if end != next_start {
+                // like for polygons, joins always turn left (see `Polygon::inner_paths`)
+                paths.push(ElementaryPath::Arc(Arc::new(
+                    end,
+                    next_start,
+                    *edge[index].end(),
+                    radius,
+                    false,
+                )));
+            }
+        }
+        paths
+    }
+
+    /// Return vector of elementary paths obtained when displacing all paths externally
+    /// (on their left side) by given radius, all joined by arcs.
+    pub(crate) fn outer_paths(
+        &self,
+        radius: f64,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<ElementaryPath> {
+        // our left side is the right side of the reversed pocket
+        let mut reversed = self.clone();
+        reversed.reverse();
+        reversed
+            .inner_paths(radius, points_hasher)
+            .iter()
+            .rev()
+            .map(|p| p.reverse())
+            .collect()
+    }
+
+    /// Return the part of our edge going from first projected point to second one
+    /// (projections give positions on our edge).
+    pub(crate) fn part_between(&self, start: &Projection, end: &Projection) -> Vec<ElementaryPath> {
+        let edge = &self.edge;
+        let same_edge = start.index == end.index && start.parameter <= end.parameter;
+        if same_edge || edge.len() == 1 {
+            // circles always take this branch
+            return sub_path(&edge[start.index], start.point, end.point)
+                .into_iter()
+                .collect();
+        }
+        let mut parts: Vec<ElementaryPath> =
+            sub_path(&edge[start.index], start.point, *edge[start.index].end())
+                .into_iter()
+                .collect();
+        let mut index = (start.index + 1) % edge.len();
+        while index != end.index {
+            parts.push(edge[index]);
+            index = (index + 1) % edge.len();
+        }
+        parts.extend(sub_path(
+            &edge[end.index],
+            *edge[end.index].start(),
+            end.point,
+        ));
+        parts
+    }
+
     /// Return the area of the polygon obtained when converting arcs to segments.
+    /// Pockets with less than three points are flat.
     pub fn polygon_area(&self) -> f64 {
+        if self.edge.len() < 3 {
+            return 0.0;
+        }
         let polygon = Polygon::new(
             self.edge
                 .iter()
@@ -38,3 +194,65 @@ impl Pocket {
         polygon.area()
     }
 }
+
+/// Maximal distance between bezier curves and the segments replacing them when offsetting.
+const BEZIER_OFFSET_PRECISION: f64 = 0.0001;
+
+/// Displace given path on its right side by given radius.
+/// Return new start and end points together with the displaced path
+/// (arcs collapsing on their center have no path).
+fn inner_parallel_path(
+    path: &ElementaryPath,
+    radius: f64,
+    points_hasher: &mut PointsHash,
+) -> (Point, Point, Option<ElementaryPath>) {
+    match *path {
+        ElementaryPath::Segment(ref s) => {
+            let displaced = ElementaryPath::parallel_segment(s, radius, true, points_hasher);
+            (*displaced.start(), *displaced.end(), Some(displaced))
+        }
+        ElementaryPath::Arc(ref a) => {
+            // the right side is towards the center when turning clockwise
+            let new_radius = if a.clockwise {
+                a.radius - radius
+            } else {
+                a.radius + radius
+            };
+            let ratio = new_radius / a.radius;
+            let start = points_hasher.add(a.center + (a.start - a.center) * ratio);
+            let end = points_hasher.add(a.center + (a.end - a.center) * ratio);
+            if is_almost(new_radius, 0.0) || start == end {
+                (start, start, None)
+            } else {
+                // a negative radius inverts the arc around its center
+                let arc = Arc::new(start, end, a.center, new_radius.abs(), a.clockwise);
+                (start, end, Some(ElementaryPath::Arc(arc)))
+            }
+        }
+        ElementaryPath::Circle(ref c) => {
+            let new_radius = if c.clockwise {
+                c.radius - radius
+            } else {
+                c.radius + radius
+            };
+            let start =
+                points_hasher.add(c.center + (c.start - c.center) * (new_radius / c.radius));
+            if new_radius < 0.0 || is_almost(new_radius, 0.0) {
+                (start, start, None) // a lone circle has no other path to make an inverted one valid
+            } else {
+                let circle = Circle::new(start, c.center, new_radius, c.clockwise);
+                (start, start, Some(ElementaryPath::Circle(circle)))
+            }
+        }
+        ElementaryPath::Bezier(_) => panic!("bezier curves need to be flattened before offsetting"),
+    }
+}
+
+/// Return part of given path between given points unless they are the same.
+fn sub_path(path: &ElementaryPath, start: Point, end: Point) -> Option<ElementaryPath> {
+    if start.is_almost(&end) {
+        None
+    } else {
+        Some(path.sub_path(start, end))
+    }
+}
@@ -62,7 +62,7 @@ fn build_pocket(
         ));
     }
     let pocket = Pocket::new(edge);
-    if pocket.polygon_area() < 0.00001 {
+    if pocket.area() < 0.00001 {
         None
     } else {
         Some(pocket)
@@ -0,0 +1,186 @@
+//! Adaptive (constant engagement) clearing with trochoidal loops.
+//!
+//! The tool circles around centers moving slowly along concentric contours.
+//! Each loop only removes a thin crescent of material whose width is the
+//! distance between successive centers, bounding the engagement angle.
+//! Contours are entered by ramps of loops moving in from already cleared material.
+use super::ContourTree;
+use std::f64::consts::PI;
+use std::iter::once;
+use {Arc, ElementaryPath, HoledPocket, Path, Point, PointsHash, Segment, Vector};
+
+/// Compute trochoidal toolpaths clearing given pocket (the region reachable by the tool's center)
+/// with a tool of given radius, never engaging the tool by more than `max_engagement` radians.
+/// Loops have radius `trochoid_radius` (smaller than the tool's radius) and are centered on
+/// concentric contours separated by twice this radius.
+/// We return one path for each contour, innermost contours first.
+///
+/// Each path starts by a ramp of loops entering the contour without exceeding the engagement
+/// limit : loops move in from the (already cleared) next inner contour or, for
+/// innermost contours, grow from the start point (where the tool needs a helical or
+/// pre-drilled entry).
+///
+/// # Example
+/// ```
+/// use grouille::pocketing::adaptive;
+/// use grouille::{ElementaryPath, HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 20.0), Vec::new());
+/// let pocket = &square.offset(2.0, &mut hasher)[0];
+/// let toolpaths = adaptive(pocket, 2.0, 1.0, std::f64::consts::PI / 3.0, &mut hasher);
+/// assert_eq!(toolpaths.len(), 4);
+/// // contours start in material already cleared by the tool (of radius 2) on previous ones
+/// for (inner, outer) in toolpaths.iter().zip(toolpaths.iter().skip(1)) {
+///     assert!(inner.projection(outer.start()).distance < 2.0);
+/// }
+/// assert!(toolpaths[1].paths().iter().all(|p| match p {
+///     ElementaryPath::Arc(a) => (a.radius - 1.0).abs() < 0.000001,
+///     ElementaryPath::Segment(_) => true,
+///     _ => false,
+/// }));
+/// // loops around holes turn the other way, like the contours they follow
+/// let mut hole = Polygon::square(8.0, 8.0, 4.0);
+/// hole.reverse();
+/// let holed_square = HoledPolygon::new(Polygon::square(0.0, 0.0, 20.0), vec![hole]);
+/// let pocket = &holed_square.offset(2.0, &mut hasher)[0];
+/// let toolpaths = adaptive(pocket, 2.0, 1.0, std::f64::consts::PI / 3.0, &mut hasher);
+/// let arcs_directions = |toolpath: &grouille::Path| -> Vec<bool> {
+///     toolpath
+///         .paths()
+///         .iter()
+///         .filter_map(|p| match p {
+///             ElementaryPath::Arc(a) => Some(a.clockwise),
+///             _ => None,
+///         })
+///         .collect()
+/// };
+/// assert_eq!(toolpaths.len(), 2);
+/// assert!(arcs_directions(&toolpaths[0]).iter().all(|&clockwise| clockwise));
+/// assert!(arcs_directions(&toolpaths[1]).iter().all(|&clockwise| !clockwise));
+/// ```
+pub fn adaptive(
+    pocket: &HoledPocket,
+    tool_radius: f64,
+    trochoid_radius: f64,
+    max_engagement: f64,
+    points_hasher: &mut PointsHash,
+) -> Vec<Path> {
+    assert!(trochoid_radius > 0.0 && trochoid_radius < tool_radius);
+    assert!(max_engagement > 0.0 && max_engagement <= PI);
+    let pitch = tool_radius * (1.0 - max_engagement.cos());
+    let mut toolpaths = Vec::new();
+    for contour in pocket.offset(trochoid_radius, points_hasher) {
+        let tree = ContourTree::new(contour, 2.0 * trochoid_radius, points_hasher);
+        tree_toolpaths(tree, trochoid_radius, pitch, &mut toolpaths);
+    }
+    toolpaths
+}
+
+/// Add toolpaths for all contours of given tree, from the inside out
+/// (like `ContourTree::into_toolpaths`).
+fn tree_toolpaths(tree: ContourTree, radius: f64, pitch: f64, toolpaths: &mut Vec<Path>) {
+    let ContourTree { contour, children } = tree;
+    let cleared_inside = !children.is_empty();
+    for child in children {
+        tree_toolpaths(child, radius, pitch, toolpaths);
+    }
+    for pocket in once(&contour.outer_pocket).chain(contour.holes.iter()) {
+        // outer loops are clockwise, holes counter clockwise
+        let clockwise = pocket.area() > 0.0;
+        toolpaths.push(trochoid(
+            &pocket.to_path(),
+            clockwise,
+            radius,
+            pitch,
+            cleared_inside,
+        ));
+    }
+}
+
+/// Return trochoidal path following given closed path (turning in given direction).
+/// Loops start on the left of the followed path (where the material is left).
+/// We first ramp into the first loop, moving in from the cleared side at distance twice the
+/// radius on the right (if `cleared_inside`) or growing loops from the first center.
+/// After the ramp the path loops back to the start of the first loop.
+fn trochoid(
+    followed: &Path,
+    clockwise: bool,
+    radius: f64,
+    pitch: f64,
+    cleared_inside: bool,
+) -> Path {
+    let length = followed.length();
+    let loops_number = ((length / pitch).ceil() as usize).max(1);
+    let step = length / loops_number as f64;
+    // loops are given by center, unit vector towards their start and radius
+    let entry_normal = left_normal(followed, 0.0);
+    let ramp_number =
+        ((if cleared_inside { 2.0 * radius } else { radius } / pitch).ceil() as usize).max(1);
+    let ramp = (0..ramp_number).map(|i| {
+        let ratio = i as f64 / ramp_number as f64;
+        if cleared_inside {
+            let center = *followed.start() + entry_normal * (-2.0 * radius * (1.0 - ratio));
+            (center, entry_normal, radius)
+        } else {
+            (
+                *followed.start(),
+                entry_normal,
+                radius * (i + 1) as f64 / ramp_number as f64,
+            )
+        }
+    });
+    let contour_loops = (0..loops_number).map(|i| {
+        let distance = i as f64 * step;
+        (
+            followed.point_at(distance),
+            left_normal(followed, distance),
+            radius,
+        )
+    });
+    // growing loops end with the first contour loop
+    let skipped = if cleared_inside { 0 } else { 1 };
+    let loops: Vec<(Point, Vector, f64)> = ramp
+        .take(ramp_number - skipped)
+        .chain(contour_loops)
+        .collect();
+    let first_loop = ramp_number - skipped;
+
+    let mut paths = Vec::with_capacity(3 * loops.len());
+    for (index, &(center, normal, loop_radius)) in loops.iter().enumerate() {
+        let start = center + normal * loop_radius;
+        let opposite = center + normal * -loop_radius;
+        // turn like the followed path
+        paths.push(ElementaryPath::Arc(Arc::new(
+            start,
+            opposite,
+            center,
+            loop_radius,
+            clockwise,
+        )));
+        paths.push(ElementaryPath::Arc(Arc::new(
+            opposite,
+            start,
+            center,
+            loop_radius,
+            clockwise,
+        )));
+        let (next_center, next_normal, next_radius) =
+            loops.get(index + 1).unwrap_or(&loops[first_loop]);
+        let next_start = *next_center + *next_normal * *next_radius;
+        if !start.is_almost(&next_start) {
+            paths.push(ElementaryPath::Segment(Segment::new(start, next_start)));
+        }
+    }
+    Path::new(paths)
+}
+
+/// Return unit vector on the left of given closed path at given distance from its start.
+fn left_normal(path: &Path, distance: f64) -> Vector {
+    let length = path.length();
+    let delta = (length / 1000.0).min(0.001);
+    let before = path.point_at((distance - delta).rem_euclid(length));
+    let after = path.point_at((distance + delta).rem_euclid(length));
+    let tangent = after - before;
+    let normal = tangent.perpendicular_vector() * -1.0;
+    normal * (1.0 / normal.norm())
+}
@@ -0,0 +1,81 @@
+//! Concentric (contour-parallel) pocketing.
+use std::iter::once;
+use {HoledPocket, HoledPolygon, Pocket, PointsHash};
+
+/// Successive inward offsets of a region.
+/// Each node's children are the regions obtained by offsetting its contour
+/// by the stepover (a contour splits into several children when islands appear).
+#[derive(Debug)]
+pub struct ContourTree {
+    /// Contour at this level
+    pub contour: HoledPocket,
+    /// Contours at next level inside this one
+    pub children: Vec<ContourTree>,
+}
+
+impl ContourTree {
+    /// Build the tree of all successive offsets of given contour until the region vanishes.
+    pub(crate) fn new(contour: HoledPocket, stepover: f64, points_hasher: &mut PointsHash) -> Self {
+        let children = contour
+            .offset(stepover, points_hasher)
+            .into_iter()
+            .map(|child| ContourTree::new(child, stepover, points_hasher))
+            .collect();
+        ContourTree { contour, children }
+    }
+
+    /// Return the number of levels in the tree.
+    pub fn depth(&self) -> usize {
+        1 + self.children.iter().map(|c| c.depth()).max().unwrap_or(0)
+    }
+
+    /// Return all closed loops in machining order.
+    /// Loops are emitted from the inside out : each subtree is completely milled
+    /// before its contour, outer pockets coming before their holes.
+    pub fn into_toolpaths(self) -> Vec<Pocket> {
+        let mut toolpaths = Vec::new();
+        self.append_toolpaths(&mut toolpaths);
+        toolpaths
+    }
+
+    fn append_toolpaths(self, toolpaths: &mut Vec<Pocket>) {
+        let ContourTree { contour, children } = self;
+        for child in children {
+            child.append_toolpaths(toolpaths);
+        }
+        toolpaths.extend(once(contour.outer_pocket).chain(contour.holes));
+    }
+}
+
+/// Compute all concentric contours for milling given region with a tool of given radius.
+/// The first level is the region offset by the tool radius and each next level
+/// is offset by the stepover from the previous one.
+/// We return one tree for each part of the first level.
+///
+/// # Example
+/// ```
+/// use grouille::pocketing::concentric_contours;
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), Vec::new());
+/// let trees = concentric_contours(&square, 1.0, 1.5, &mut hasher);
+/// assert_eq!(trees.len(), 1);
+/// // sides are 8, 5 and 2
+/// assert_eq!(trees[0].depth(), 3);
+/// let toolpaths: Vec<_> = trees.into_iter().flat_map(|t| t.into_toolpaths()).collect();
+/// assert!((toolpaths[0].area() - 4.0).abs() < 0.00001);
+/// assert!((toolpaths[2].area() - 64.0).abs() < 0.00001);
+/// ```
+pub fn concentric_contours(
+    region: &HoledPolygon,
+    tool_radius: f64,
+    stepover: f64,
+    points_hasher: &mut PointsHash,
+) -> Vec<ContourTree> {
+    assert!(stepover > 0.0);
+    region
+        .offset(tool_radius, points_hasher)
+        .into_iter()
+        .map(|contour| ContourTree::new(contour, stepover, points_hasher))
+        .collect()
+}
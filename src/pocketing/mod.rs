@@ -0,0 +1,14 @@
+//! Pocketing strategies : toolpaths clearing all material inside a shape.
+//!
+//! All strategies take a tool radius (distance kept between the tool center and
+//! the shape's edges) and a stepover (distance between successive passes).
+pub mod concentric;
+pub use self::concentric::{concentric_contours, ContourTree};
+pub mod zigzag;
+pub use self::zigzag::zigzag;
+pub mod spiral;
+pub use self::spiral::spiral;
+pub mod adaptive;
+pub use self::adaptive::adaptive;
+pub mod rest;
+pub use self::rest::{rest_material, swept_region, toolpaths_swept_region};
@@ -0,0 +1,95 @@
+//! Rest machining : material left by a large tool, to be removed by a smaller one.
+use boolean::{pockets_difference, pockets_intersection, pockets_union};
+use offset::winding_region;
+use {HoledPocket, HoledPolygon, Path, Pocket, PointsHash};
+
+/// Return the part of given region swept by a tool of given radius
+/// whose center goes everywhere it can without leaving the region.
+pub fn swept_region(
+    region: &HoledPolygon,
+    tool_radius: f64,
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    let swept_parts: Vec<HoledPocket> = region
+        .offset(tool_radius, points_hasher)
+        .iter()
+        .flat_map(|reachable| reachable.outward_offset(tool_radius, points_hasher))
+        .collect();
+    // parts might overlap since several tool positions can sweep the same material
+    pockets_union(&swept_parts, &[], points_hasher)
+}
+
+/// Return the region swept by a tool of given radius following given toolpaths.
+///
+/// # Example
+/// ```
+/// use grouille::pocketing::toolpaths_swept_region;
+/// use grouille::{ElementaryPath, Path, Point, PointsHash, Segment};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let segment = Segment::new(Point::new(0.0, 0.0), Point::new(4.0, 0.0));
+/// let toolpath = Path::new(vec![ElementaryPath::Segment(segment)]);
+/// let swept = toolpaths_swept_region(&[toolpath], 1.0, &mut hasher);
+/// assert_eq!(swept.len(), 1);
+/// assert!((swept[0].outer_pocket.area() - 8.0 - std::f64::consts::PI).abs() < 0.00001);
+/// ```
+pub fn toolpaths_swept_region(
+    toolpaths: &[Path],
+    tool_radius: f64,
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    // grow each toolpath as a flat loop going there and back
+    let raw_loops = toolpaths
+        .iter()
+        .map(|toolpath| {
+            let flat_loop = Pocket::new(
+                toolpath
+                    .paths()
+                    .iter()
+                    .cloned()
+                    .chain(toolpath.paths().iter().rev().map(|p| p.reverse()))
+                    .collect(),
+            );
+            flat_loop.outer_paths(tool_radius, points_hasher)
+        })
+        .collect();
+    winding_region(raw_loops, points_hasher)
+}
+
+/// Return material of given region left uncut after following given roughing toolpaths
+/// with a tool of radius `roughing_radius` but which can be removed by a tool of radius
+/// `finishing_radius`.
+///
+/// # Example
+/// ```
+/// use grouille::pocketing::{concentric_contours, rest_material};
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), Vec::new());
+/// let roughing: Vec<_> = concentric_contours(&square, 1.0, 1.5, &mut hasher)
+///     .into_iter()
+///     .flat_map(|tree| tree.into_toolpaths())
+///     .map(|contour| contour.to_path())
+///     .collect();
+/// // each corner leaves r * r * (1 - pi / 4) of material
+/// let rest = rest_material(&square, &roughing, 1.0, 0.5, &mut hasher);
+/// assert_eq!(rest.len(), 4);
+/// let area: f64 = rest.iter().map(|r| r.outer_pocket.area()).sum();
+/// let expected = 4.0 * (1.0 - 0.25) * (1.0 - std::f64::consts::PI / 4.0);
+/// assert!((area - expected).abs() < 0.00001);
+/// // without the innermost loop we also leave material in the middle
+/// let rest = rest_material(&square, &roughing[1..], 1.0, 0.5, &mut hasher);
+/// assert_eq!(rest.len(), 5);
+/// ```
+pub fn rest_material(
+    region: &HoledPolygon,
+    roughing_toolpaths: &[Path],
+    roughing_radius: f64,
+    finishing_radius: f64,
+    points_hasher: &mut PointsHash,
+) -> Vec<HoledPocket> {
+    assert!(finishing_radius < roughing_radius);
+    let roughed = toolpaths_swept_region(roughing_toolpaths, roughing_radius, points_hasher);
+    let finished = swept_region(region, finishing_radius, points_hasher);
+    let left = pockets_difference(&[region.to_holed_pocket()], &roughed, points_hasher);
+    pockets_intersection(&left, &finished, points_hasher)
+}
@@ -0,0 +1,182 @@
+//! Spiral pocketing.
+//!
+//! Successive concentric contours are morphed one into the next, giving
+//! continuous toolpaths without the step transitions of concentric loops.
+use super::ContourTree;
+use boolean::winding;
+use std::iter::once;
+use {ElementaryPath, HoledPocket, Path, Pocket, Point, PointsHash, Segment};
+
+/// Distance between morphing samples, as a fraction of the stepover.
+const MORPH_SAMPLING: f64 = 0.125;
+
+/// Compute spiral toolpaths for milling given pocket with a tool of given radius
+/// and given stepover.
+/// The first loop is the pocket offset by the tool radius and each next one is
+/// offset by the stepover from the previous one.
+/// Contours are morphed as long as the region keeps the same topology
+/// (no split and same number of holes). Each spiral starts with a full turn
+/// on its outermost loop and ends with a full turn on its innermost one.
+/// Holes get their own spirals, growing around them. Each one is entered by a straight move
+/// from the end of the previous spiral, which stays in the pocket but might cut at full
+/// engagement. If this move would leave the pocket the hole spiral is a separate toolpath.
+///
+/// # Example
+/// ```
+/// use grouille::pocketing::spiral;
+/// use grouille::{Circle, ElementaryPath, HoledPocket, Pocket, Point, PointsHash};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let circle = Circle::new(Point::new(4.0, 0.0), Point::origin(), 4.0, true);
+/// let disk = HoledPocket::new(Pocket::new(vec![ElementaryPath::Circle(circle)]), Vec::new());
+/// let spirals = spiral(&disk, 1.0, 1.0, &mut hasher);
+/// assert_eq!(spirals.len(), 1);
+/// assert!(spirals[0].start().is_almost(&Point::new(3.0, 0.0)));
+/// assert!(spirals[0].end().is_almost(&Point::new(1.0, 0.0)));
+/// // hole spirals are linked to the outer one
+/// let hole = Circle::new(Point::new(1.0, 0.0), Point::origin(), 1.0, false);
+/// let ring = HoledPocket::new(
+///     Pocket::new(vec![ElementaryPath::Circle(circle)]),
+///     vec![Pocket::new(vec![ElementaryPath::Circle(hole)])],
+/// );
+/// let spirals = spiral(&ring, 0.5, 0.5, &mut hasher);
+/// assert_eq!(spirals.len(), 1);
+/// assert!(spirals[0].end().is_almost(&Point::new(2.0, 0.0)));
+/// ```
+pub fn spiral(
+    pocket: &HoledPocket,
+    tool_radius: f64,
+    stepover: f64,
+    points_hasher: &mut PointsHash,
+) -> Vec<Path> {
+    assert!(stepover > 0.0);
+    let mut spirals = Vec::new();
+    for contour in pocket.offset(tool_radius, points_hasher) {
+        tree_spirals(
+            ContourTree::new(contour, stepover, points_hasher),
+            stepover,
+            &mut spirals,
+        );
+    }
+    spirals
+}
+
+/// Add spirals for all levels of given tree.
+fn tree_spirals(tree: ContourTree, stepover: f64, spirals: &mut Vec<Path>) {
+    // follow levels while topology does not change
+    let mut levels = vec![tree.contour];
+    let mut children = tree.children;
+    while children.len() == 1 && children[0].contour.holes.len() == levels[0].holes.len() {
+        let child = children.pop().unwrap();
+        levels.push(child.contour);
+        children = child.children;
+    }
+
+    let outer_loops: Vec<&Pocket> = levels.iter().map(|l| &l.outer_pocket).collect();
+    let mut spiral = loops_spiral(
+        &outer_loops,
+        levels[0].outer_pocket.edge()[0].start(),
+        stepover,
+    );
+    let mut holes_loops = Vec::new();
+    for hole in &levels[0].holes {
+        let mut hole_loops = vec![hole];
+        for level in &levels[1..] {
+            let start = *hole_loops.last().unwrap().edge()[0].start();
+            let closest_hole = level
+                .holes
+                .iter()
+                .min_by(|h1, h2| {
+                    let distance1 = h1.projection(&start).distance;
+                    let distance2 = h2.projection(&start).distance;
+                    distance1.partial_cmp(&distance2).unwrap()
+                })
+                .unwrap();
+            hole_loops.push(closest_hole);
+        }
+        holes_loops.push(hole_loops);
+    }
+    // go to the closest remaining hole each time
+    let boundary: Vec<Pocket> = once(&levels[0].outer_pocket)
+        .chain(levels[0].holes.iter())
+        .cloned()
+        .collect();
+    while !holes_loops.is_empty() {
+        let position = *spiral.end();
+        let distance = |loops: &Vec<&Pocket>| loops[0].projection(&position).distance;
+        let closest = (0..holes_loops.len())
+            .min_by(|&i1, &i2| {
+                distance(&holes_loops[i1])
+                    .partial_cmp(&distance(&holes_loops[i2]))
+                    .unwrap()
+            })
+            .unwrap();
+        let hole_loops = holes_loops.swap_remove(closest);
+        let entry = hole_loops[0].projection(&position).point;
+        let hole_spiral = loops_spiral(&hole_loops, &entry, stepover);
+        let link = Segment::new(position, *hole_spiral.start());
+        if position.is_almost(hole_spiral.start()) {
+            spiral.append(hole_spiral);
+        } else if stays_inside(&link, &boundary) {
+            spiral.append(Path::new(vec![ElementaryPath::Segment(link)]));
+            spiral.append(hole_spiral);
+        } else {
+            spirals.push(spiral);
+            spiral = hole_spiral;
+        }
+    }
+    spirals.push(spiral);
+
+    for child in children {
+        tree_spirals(child, stepover, spirals);
+    }
+}
+
+/// Is given segment (starting and ending on given loops) inside the region they delimit ?
+fn stays_inside(segment: &Segment, loops: &[Pocket]) -> bool {
+    let path = ElementaryPath::Segment(*segment);
+    let crosses = loops
+        .iter()
+        .flat_map(|pocket| pocket.edge().iter())
+        .flat_map(|edge_path| edge_path.intersections_with(&path))
+        .any(|p| !p.is_almost(&segment.start) && !p.is_almost(&segment.end));
+    !crosses && winding(&segment.start.center_with(&segment.end), loops) > 0
+}
+
+/// Morph given successive loops one into the next, starting from the point of the first loop
+/// closest to given point.
+fn loops_spiral(loops: &[&Pocket], start: &Point, stepover: f64) -> Path {
+    let mut spiral = loops[0].to_path().starting_at(start);
+    let mut current = spiral.clone();
+    for next_loop in &loops[1..] {
+        let next = next_loop.to_path().starting_at(current.start());
+        let morph = morph_paths(&current, &next, stepover);
+        if !morph.is_empty() {
+            spiral.append(Path::new(morph));
+        }
+        current = next;
+    }
+    if loops.len() > 1 {
+        spiral.append(current);
+    }
+    spiral
+}
+
+/// Return segments going from the start of the first path to the start of the second one,
+/// moving progressively from the first one to the second one along one turn.
+fn morph_paths(from: &Path, to: &Path, stepover: f64) -> Vec<ElementaryPath> {
+    let length = from.length();
+    let samples = ((length / (stepover * MORPH_SAMPLING)).ceil() as usize).max(1);
+    let mut points = Vec::with_capacity(samples + 1);
+    for sample in 0..samples {
+        let ratio = sample as f64 / samples as f64;
+        let point = from.point_at(length * ratio);
+        let target = to.projection(&point).point;
+        points.push(point + (target - point) * ratio);
+    }
+    points.push(*to.start());
+    points
+        .windows(2)
+        .filter(|p| !p[0].is_almost(&p[1]))
+        .map(|p| ElementaryPath::Segment(Segment::new(p[0], p[1])))
+        .collect()
+}
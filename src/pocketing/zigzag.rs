@@ -0,0 +1,202 @@
+//! Zigzag (raster) pocketing.
+//!
+//! Parallel lines are clipped against the pocket and consecutive lines are
+//! connected by following the boundary whenever it stays between them.
+use std::iter::once;
+use {ElementaryPath, HoledPocket, Line, Path, Pocket, Point, Projection, Segment, Vector};
+
+/// Maximal error when approximating the pocket by a polygon to find its extent.
+const EXTENT_PRECISION: f64 = 0.0001;
+
+/// A point where a raster line crosses the boundary.
+struct Crossing {
+    /// position of the crossing on the crossed pocket
+    projection: Projection,
+    /// index of the raster line
+    line: usize,
+    /// index of the crossed pocket (0 for outer pocket, i+1 for hole i)
+    pocket: usize,
+    /// index of the raster segment starting or ending here (if any)
+    segment: Option<usize>,
+}
+
+/// Compute zigzag toolpaths clearing given pocket with lines at given angle
+/// separated by at most `stepover`.
+/// Lines are connected along the boundary into as few strokes as possible
+/// (moving up one line at a time) and never cross the holes.
+///
+/// # Example
+/// ```
+/// use grouille::pocketing::zigzag;
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// let square = HoledPolygon::new(Polygon::square(0.0, 0.0, 6.0), Vec::new());
+/// // a 4x4 square pocket
+/// let pocket = &square.offset(1.0, &mut hasher)[0];
+/// let strokes = zigzag(pocket, 0.0, 1.0);
+/// // one stroke : four lines of length 4 and three connections of length 1
+/// assert_eq!(strokes.len(), 1);
+/// assert!((strokes[0].length() - 19.0).abs() < 0.000001);
+/// ```
+pub fn zigzag(pocket: &HoledPocket, angle: f64, stepover: f64) -> Vec<Path> {
+    assert!(stepover > 0.0);
+    let direction = Vector::new(angle.cos(), angle.sin());
+    let reference = Line::new(Point::origin(), direction);
+    let pockets: Vec<&Pocket> = once(&pocket.outer_pocket)
+        .chain(pocket.holes.iter())
+        .collect();
+
+    // spread the lines evenly over the pocket's extent
+    let outline = pocket.outer_pocket.to_polygon(EXTENT_PRECISION);
+    let (min_side, max_side) = outline
+        .points()
+        .iter()
+        .map(|p| reference.side(p))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), side| {
+            (min.min(side), max.max(side))
+        });
+    let width = max_side - min_side;
+    let lines_number = ((width / stepover).ceil() as usize).max(1);
+    let spacing = width / lines_number as f64;
+    let normal = direction.perpendicular_vector();
+
+    let mut crossings = Vec::new();
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+    for line_index in 0..lines_number {
+        let offset = min_side + spacing * (line_index as f64 + 0.5);
+        let line = Line::new(Point::origin() + normal * offset, direction);
+        let mut line_crossings: Vec<(Point, i32, usize)> = pockets
+            .iter()
+            .enumerate()
+            .flat_map(|(pocket_index, p)| {
+                line.crossings_with_pocket(p)
+                    .into_iter()
+                    .map(move |(point, way)| (point, way, pocket_index))
+            })
+            .collect();
+        line_crossings.sort_by(|c1, c2| {
+            line.parameter_of(&c1.0)
+                .partial_cmp(&line.parameter_of(&c2.0))
+                .unwrap()
+        });
+        // we are inside when the winding number is not zero
+        let mut winding = 0;
+        let mut segment_start = None;
+        for (point, way, pocket_index) in line_crossings {
+            let projection = Projection {
+                point,
+                ..pockets[pocket_index].projection(&point)
+            };
+            let crossing_index = crossings.len();
+            crossings.push(Crossing {
+                projection,
+                line: line_index,
+                pocket: pocket_index,
+                segment: None,
+            });
+            let was_inside = winding != 0;
+            winding += way;
+            if !was_inside && winding != 0 {
+                segment_start = Some(crossing_index);
+            } else if was_inside && winding == 0 {
+                let start = segment_start.take().unwrap();
+                if !crossings[start].projection.point.is_almost(&point) {
+                    crossings[start].segment = Some(segments.len());
+                    crossings[crossing_index].segment = Some(segments.len());
+                    segments.push((start, crossing_index));
+                }
+            }
+        }
+    }
+
+    // sort crossings along each pocket's boundary
+    let mut boundaries: Vec<Vec<usize>> = vec![Vec::new(); pockets.len()];
+    for (index, crossing) in crossings.iter().enumerate() {
+        boundaries[crossing.pocket].push(index);
+    }
+    let mut ranks = vec![0; crossings.len()];
+    for boundary in &mut boundaries {
+        boundary.sort_by(|&c1, &c2| {
+            let key1 = (
+                crossings[c1].projection.index,
+                crossings[c1].projection.parameter,
+            );
+            let key2 = (
+                crossings[c2].projection.index,
+                crossings[c2].projection.parameter,
+            );
+            key1.partial_cmp(&key2).unwrap()
+        });
+        for (rank, &crossing) in boundary.iter().enumerate() {
+            ranks[crossing] = rank;
+        }
+    }
+
+    // segments are already sorted by line
+    let mut used = vec![false; segments.len()];
+    let mut strokes = Vec::new();
+    for first_segment in 0..segments.len() {
+        if used[first_segment] {
+            continue;
+        }
+        used[first_segment] = true;
+        let (start, mut end) = segments[first_segment];
+        let mut paths = vec![raster_segment(&crossings, start, end)];
+        loop {
+            let exit = &crossings[end];
+            let boundary = &boundaries[exit.pocket];
+            let size = boundary.len();
+            let next = boundary[(ranks[end] + 1) % size];
+            let previous = boundary[(ranks[end] + size - 1) % size];
+            let candidates = [(next, true), (previous, false)];
+            let connection = candidates
+                .iter()
+                .filter(|&&(c, _)| crossings[c].line == exit.line + 1)
+                .filter_map(|&(c, forward)| {
+                    crossings[c]
+                        .segment
+                        .filter(|&s| !used[s])
+                        .map(|s| (c, forward, s))
+                })
+                .map(|(c, forward, s)| {
+                    let boundary = pockets[exit.pocket];
+                    let connection = if forward {
+                        boundary.part_between(&crossings[end].projection, &crossings[c].projection)
+                    } else {
+                        boundary
+                            .part_between(&crossings[c].projection, &crossings[end].projection)
+                            .iter()
+                            .rev()
+                            .map(|p| p.reverse())
+                            .collect()
+                    };
+                    (c, s, connection)
+                })
+                .min_by(|(_, _, p1), (_, _, p2)| {
+                    let length1: f64 = p1.iter().map(|p| p.length()).sum();
+                    let length2: f64 = p2.iter().map(|p| p.length()).sum();
+                    length1.partial_cmp(&length2).unwrap()
+                });
+            if let Some((entry, segment, connection)) = connection {
+                used[segment] = true;
+                let (s1, s2) = segments[segment];
+                let other = if s1 == entry { s2 } else { s1 };
+                paths.extend(connection);
+                paths.push(raster_segment(&crossings, entry, other));
+                end = other;
+            } else {
+                break;
+            }
+        }
+        strokes.push(Path::new(paths));
+    }
+    strokes
+}
+
+/// Return the raster segment between the two given crossings.
+fn raster_segment(crossings: &[Crossing], start: usize, end: usize) -> ElementaryPath {
+    ElementaryPath::Segment(Segment::new(
+        crossings[start].projection.point,
+        crossings[end].projection.point,
+    ))
+}
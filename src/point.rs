@@ -3,6 +3,7 @@ use std::ops::{Add, Div, Mul, Sub};
 use utils::is_almost;
 
 #[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// 2d point
 pub struct Point {
     /// x coordinate
@@ -13,7 +14,8 @@ pub struct Point {
 
 impl Eq for Point {}
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// a vector stores the difference between two points
 pub struct Vector {
     /// x component
@@ -93,6 +95,17 @@ impl Vector {
         (self.x * self.x + self.y * self.y).sqrt()
     }
 
+    /// Return the scalar product with other vector.
+    pub fn scalar_product(&self, other: &Vector) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Return the cross product (z coordinate) with other vector.
+    /// It is positive if other vector points towards increasing angles.
+    pub fn cross_product(&self, other: &Vector) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
     /// Compute angle between vector and x axis (will be strictly less than PI).
     pub fn angle(&self) -> f64 {
         self.y.atan2(self.x)
@@ -127,6 +140,26 @@ impl<'a, 'b> Add<&'a Vector> for &'b Point {
     }
 }
 
+impl Add<Vector> for Vector {
+    type Output = Vector;
+    fn add(self, other: Vector) -> Self::Output {
+        Vector {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub<Vector> for Vector {
+    type Output = Vector;
+    fn sub(self, other: Vector) -> Self::Output {
+        Vector {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
 impl Sub<Point> for Point {
     type Output = Vector;
     fn sub(self, other: Point) -> Vector {
@@ -223,6 +256,7 @@ impl<'a> Div<f64> for &'a Vector {
 
 /// small point in 3d with no methods
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point3 {
     /// x coordinate
     pub x: f64,
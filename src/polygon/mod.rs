@@ -1,12 +1,18 @@
 //! Polygon module.
+use offset::{winding_region, Join};
+use projection::closest_projection;
 use std::iter::once;
 use streaming_iterator::StreamingIterator;
-use {utils::iterators::GrouilleSlice, Arc, ElementaryPath, Point, PointsHash, Quadrant, Segment};
+use utils::is_almost;
+use {
+    utils::iterators::GrouilleSlice, Arc, ElementaryPath, HoledPocket, Line, Pocket, Point,
+    PointsHash, Projection, Quadrant, Segment, Vector,
+};
 
 pub mod polygon_builder;
 
 /// Oriented polygons.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Polygon {
     /// Vector of all points forming the edge of the polygon.
     points: Vec<Point>,
@@ -182,6 +188,29 @@ impl Polygon {
         Polygon::new(final_points)
     }
 
+    /// Return closest point on our edge from given point.
+    /// Returned index is the index of the side (starting on point of same index) containing it.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::{Point, Polygon};
+    /// let square = Polygon::square(0.0, 0.0, 2.0);
+    /// let projection = square.projection(&Point::new(1.5, 1.0));
+    /// assert_eq!(projection.index, 1);
+    /// assert!(projection.point.is_almost(&Point::new(2.0, 1.0)));
+    /// assert!((projection.distance - 0.5).abs() < 0.000001);
+    /// assert!((projection.parameter - 1.0).abs() < 0.000001);
+    /// ```
+    pub fn projection(&self, point: &Point) -> Projection {
+        closest_projection(
+            self.points
+                .iter()
+                .zip(self.points.iter().cycle().skip(1))
+                .map(|(start, end)| Segment::new(*start, *end).projection(point)),
+        )
+        .expect("empty polygon")
+    }
+
     /// return all intersecting x coordinates for a given y.
     pub fn intersections_at_y<'a>(&'a self, y: f64) -> impl Iterator<Item = f64> + 'a {
         self.points
@@ -207,7 +236,18 @@ impl Polygon {
             .cloned()
     }
 
-    /// Return vector of elementary paths obtained when displacing all segments internally by given
+    /// Convert ourselves to a pocket (with segments only).
+    pub fn to_pocket(&self) -> Pocket {
+        Pocket::new(
+            self.points
+                .iter()
+                .zip(self.points.iter().cycle().skip(1))
+                .map(|(start, end)| ElementaryPath::Segment(Segment::new(*start, *end)))
+                .collect(),
+        )
+    }
+
+    /// Return the loop of elementary paths obtained when displacing all segments internally by given
     /// radius. All segments are joined by arcs.
     /// This is used in offsetter.
     pub(crate) fn inner_paths(
@@ -215,24 +255,160 @@ impl Polygon {
         radius: f64,
         points_hasher: &mut PointsHash,
     ) -> Vec<ElementaryPath> {
-        let mut segments: Vec<ElementaryPath> = self
+        let segments: Vec<ElementaryPath> = self
             .points
-            .wrapping_windows(2)
-            .map(|p| {
-                let s = Segment::new(p[0], p[1]);
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .map(|(start, end)| {
+                let s = Segment::new(*start, *end);
                 ElementaryPath::parallel_segment(&s, radius, true, points_hasher)
             })
-            .cloned()
             .collect();
+        let mut paths = Vec::with_capacity(2 * segments.len());
+        for ((segment, next_segment), corner) in segments
+            .iter()
+            .zip(segments.iter().cycle().skip(1))
+            .zip(self.points.iter().cycle().skip(1))
+        {
+            paths.push(*segment);
+            if segment.end() != next_segment.start() {
+                // joins always turn left : around reflex corners they follow the corner
+                // and around convex ones they close small loops discarded by winding numbers
+                paths.push(ElementaryPath::Arc(Arc::new(
+                    *segment.end(),
+                    *next_segment.start(),
+                    *corner,
+                    radius,
+                    false,
+                )));
+            }
+        }
+        paths
+    }
 
-        let mut arcs: Vec<ElementaryPath> = segments
-            .wrapping_windows(2)
-            .map(|s| (*s[0].end(), *s[1].start()))
-            .cloned()
-            .zip(self.points.iter().cycle().skip(2))
-            .map(|(s, c)| ElementaryPath::Arc(Arc::new(s.0, s.1, *c, radius)))
+    /// Return the loop of elementary paths obtained when displacing all segments externally
+    /// (on their left side) by given radius.
+    /// Segments are joined by given join around convex corners and by arcs around
+    /// concave ones (required for the winding number of the loop to be right).
+    pub(crate) fn outer_paths(
+        &self,
+        radius: f64,
+        join: Join,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<ElementaryPath> {
+        let count = self.points.len();
+        let segments: Vec<Segment> = (0..count)
+            .map(|i| Segment::new(self.points[i], self.points[(i + 1) % count]))
+            .filter(|s| s.start != s.end)
             .collect();
-        segments.append(&mut arcs);
-        segments
+        let displaced: Vec<ElementaryPath> = segments
+            .iter()
+            .map(|s| ElementaryPath::parallel_segment(s, radius, false, points_hasher))
+            .collect();
+        let mut paths = Vec::with_capacity(3 * displaced.len());
+        for (index, path) in displaced.iter().enumerate() {
+            let next_index = (index + 1) % displaced.len();
+            let next_path = &displaced[next_index];
+            paths.push(*path);
+            let (start, end) = (*path.end(), *next_path.start());
+            if start == end {
+                continue; // aligned segments
+            }
+            let corner = segments[index].end;
+            let incoming = segments[index].end - segments[index].start;
+            let outgoing = segments[next_index].end - segments[next_index].start;
+            // we turn right (towards increasing angles) on convex corners
+            let convex = incoming.cross_product(&outgoing) > 0.0;
+            let corner_points = match join {
+                Join::Miter(limit) if convex => {
+                    let miter = Line::new(start, incoming)
+                        .intersection_with_line(&Line::new(end, outgoing))
+                        .filter(|m| m.distance_to(&corner) <= limit * radius);
+                    miter.map_or_else(
+                        || square_corner(&corner, start, end, incoming, radius),
+                        |m| vec![m],
+                    )
+                }
+                Join::Square if convex => square_corner(&corner, start, end, incoming, radius),
+                _ => {
+                    // arcs always turn right, even around concave corners where they
+                    // go all around the corner and create loops of positive winding.
+                    paths.push(ElementaryPath::Arc(Arc::new(
+                        start, end, corner, radius, true,
+                    )));
+                    continue;
+                }
+            };
+            let corner_points: Vec<Point> = once(start)
+                .chain(corner_points.into_iter().map(|p| points_hasher.add(p)))
+                .chain(once(end))
+                .collect();
+            paths.extend(
+                corner_points
+                    .windows(2)
+                    .filter(|p| p[0] != p[1])
+                    .map(|p| ElementaryPath::Segment(Segment::new(p[0], p[1]))),
+            );
+        }
+        paths
+    }
+
+    /// Grow ourselves by given radius, joining displaced edges with given join.
+    /// We return holed pockets since growing can close some holes and round joins create arcs.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::offset::Join;
+    /// use grouille::{Polygon, PointsHash};
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let square = Polygon::square(0.0, 0.0, 2.0);
+    /// let rounded = square.outward_offset(1.0, Join::Round, &mut hasher);
+    /// assert_eq!(rounded.len(), 1);
+    /// let expected_area = 4.0 + 8.0 + std::f64::consts::PI;
+    /// assert!((rounded[0].outer_pocket.area() - expected_area).abs() < 0.00001);
+    /// let mitered = square.outward_offset(1.0, Join::Miter(2.0), &mut hasher);
+    /// assert!((mitered[0].outer_pocket.area() - 16.0).abs() < 0.00001);
+    /// // small radii still get square corners : four sides and three segments per corner
+    /// let mut fine_hasher = PointsHash::new(0.0000001);
+    /// let squared = square.outward_offset(0.0001, Join::Square, &mut fine_hasher);
+    /// assert_eq!(squared[0].outer_pocket.edge().len(), 16);
+    /// ```
+    pub fn outward_offset(
+        &self,
+        radius: f64,
+        join: Join,
+        points_hasher: &mut PointsHash,
+    ) -> Vec<HoledPocket> {
+        winding_region(
+            vec![self.outer_paths(radius, join, points_hasher)],
+            points_hasher,
+        )
+    }
+}
+
+/// Return points cutting the corner between given points, at given distance from corner.
+/// Given incoming direction is the direction of the edge arriving at the corner.
+fn square_corner(
+    corner: &Point,
+    start: Point,
+    end: Point,
+    incoming: Vector,
+    radius: f64,
+) -> Vec<Point> {
+    let bisector = (start - *corner) + (end - *corner);
+    if is_almost(bisector.norm(), 0.0) {
+        // u turn, we go straight around
+        let forward = incoming * (radius / incoming.norm());
+        return vec![start + forward, end + forward];
     }
+    let cap = Line::new(
+        *corner + bisector * (radius / bisector.norm()),
+        bisector.perpendicular_vector(),
+    );
+    let start_line = Line::new(start, (start - *corner).perpendicular_vector());
+    let end_line = Line::new(end, (end - *corner).perpendicular_vector());
+    vec![
+        cap.intersection_with_line(&start_line).unwrap_or(start),
+        cap.intersection_with_line(&end_line).unwrap_or(end),
+    ]
 }
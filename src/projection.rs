@@ -0,0 +1,44 @@
+//! Provides the `Projection` structure returned by closest point queries.
+use Point;
+
+/// Result of projecting a point on a shape (the closest point on the shape).
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Projection {
+    /// Closest point on the shape
+    pub point: Point,
+    /// Distance between the projected point and the closest point
+    pub distance: f64,
+    /// Index of the edge (polygon side or elementary path) containing the closest point
+    pub index: usize,
+    /// Distance travelled along the edge from its start to reach the closest point
+    pub parameter: f64,
+}
+
+impl Projection {
+    /// Create a new projection on first edge.
+    pub fn new(projected_point: &Point, point: Point, parameter: f64) -> Projection {
+        Projection {
+            point,
+            distance: projected_point.distance_to(&point),
+            index: 0,
+            parameter,
+        }
+    }
+
+    /// Return same projection but on edge of given index.
+    pub(crate) fn on_edge(self, index: usize) -> Projection {
+        Projection { index, ..self }
+    }
+}
+
+/// Return the closest of all given projections (each on a different edge).
+pub(crate) fn closest_projection<I: IntoIterator<Item = Projection>>(
+    projections: I,
+) -> Option<Projection> {
+    projections
+        .into_iter()
+        .enumerate()
+        .map(|(index, projection)| projection.on_edge(index))
+        .min_by(|p1, p2| p1.distance.partial_cmp(&p2.distance).unwrap())
+}
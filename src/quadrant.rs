@@ -5,6 +5,7 @@ use utils::{max, min};
 use Point;
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// A `Quadrant` delimits a rectangular region in the plane.
 pub struct Quadrant {
     /// all min values allowed for each coordinate
@@ -38,6 +39,11 @@ impl Quadrant {
         self.maxs[1] = max(self.maxs[1], other.maxs[1]);
     }
 
+    /// Do we intersect (or touch) other quadrant ?
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..2).all(|d| self.mins[d] <= other.maxs[d] && other.mins[d] <= self.maxs[d])
+    }
+
     /// Return limits for given dimension.
     pub fn limits(&self, dimension: usize) -> (f64, f64) {
         (self.mins[dimension], self.maxs[dimension])
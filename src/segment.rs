@@ -2,10 +2,11 @@
 
 use std::f64::consts::PI;
 use utils::{is_almost, min_max};
-use {CoordinatesHash, HashKey, Point};
+use {CoordinatesHash, HashKey, Point, Projection};
 
 /// 2d oriented segment
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Segment {
     /// starting point
     pub start: Point,
@@ -45,6 +46,20 @@ impl Segment {
         )
     }
 
+    /// Return closest point on ourselves from given point.
+    pub fn projection(&self, point: &Point) -> Projection {
+        let direction = self.end - self.start;
+        let length = direction.norm();
+        let parameter = ((point - self.start).scalar_product(&direction) / length)
+            .max(0.0)
+            .min(length);
+        Projection::new(
+            point,
+            self.start + direction * (parameter / length),
+            parameter,
+        )
+    }
+
     /// Returns supporting angle
     pub fn sweeping_angle(&self) -> f64 {
         let angle = (self.end - self.start).angle();
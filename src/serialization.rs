@@ -0,0 +1,90 @@
+//! Serialization of shapes storing a `Quadrant`.
+//!
+//! Quadrants are not serialized but recomputed when loading.
+//! Polygons are stored as their points, pockets and paths as their elementary paths.
+//!
+//! # Example
+//! ```
+//! extern crate grouille;
+//! extern crate serde_json;
+//! use grouille::tycat::Tycat;
+//! use grouille::{ElementaryPath, Path, Point, Polygon, Quadrant, Segment};
+//! # fn main() {
+//! let same_quadrants = |q1: Quadrant, q2: Quadrant| {
+//!     q1.limits(0) == q2.limits(0) && q1.limits(1) == q2.limits(1)
+//! };
+//!
+//! let polygon = Polygon::square(1.0, 2.0, 3.0);
+//! let loaded: Polygon = serde_json::from_str(&serde_json::to_string(&polygon).unwrap()).unwrap();
+//! assert_eq!(loaded.points(), polygon.points());
+//! assert!(same_quadrants(loaded.quadrant, polygon.quadrant));
+//!
+//! let pocket = polygon.to_pocket();
+//! let loaded: grouille::Pocket =
+//!     serde_json::from_str(&serde_json::to_string(&pocket).unwrap()).unwrap();
+//! assert_eq!(format!("{:?}", loaded.edge()), format!("{:?}", pocket.edge()));
+//! assert!(same_quadrants(Tycat::quadrant(&loaded), Tycat::quadrant(&pocket)));
+//!
+//! let path = Path::new(vec![
+//!     ElementaryPath::Segment(Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0))),
+//!     ElementaryPath::Segment(Segment::new(Point::new(1.0, 0.0), Point::new(1.0, 2.0))),
+//! ]);
+//! let loaded: Path = serde_json::from_str(&serde_json::to_string(&path).unwrap()).unwrap();
+//! assert_eq!(format!("{:?}", loaded.paths()), format!("{:?}", path.paths()));
+//! assert!(same_quadrants(Tycat::quadrant(&loaded), Tycat::quadrant(&path)));
+//! # }
+//! ```
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use {ElementaryPath, Path, Pocket, Point, Polygon};
+
+impl Serialize for Polygon {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.points().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Polygon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let points = Vec::<Point>::deserialize(deserializer)?;
+        if points.len() < 3 {
+            Err(D::Error::custom("polygons need at least 3 points"))
+        } else {
+            Ok(Polygon::new(points))
+        }
+    }
+}
+
+impl Serialize for Pocket {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.edge.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pocket {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let edge = Vec::<ElementaryPath>::deserialize(deserializer)?;
+        if edge.is_empty() {
+            Err(D::Error::custom("empty pocket"))
+        } else {
+            Ok(Pocket::new(edge))
+        }
+    }
+}
+
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.paths.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let paths = Vec::<ElementaryPath>::deserialize(deserializer)?;
+        if paths.is_empty() {
+            Err(D::Error::custom("empty path"))
+        } else {
+            Ok(Path::new(paths))
+        }
+    }
+}
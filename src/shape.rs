@@ -1,6 +1,7 @@
 //! common functions between `Polygon` and `Pocket` are abstracted through the `Shape` trait
 //! defined here.
-use crate::{ElementaryPath, Pocket, Point, Polygon, Quadrant, Segment};
+use crate::{Line, Pocket, Point, Polygon, Quadrant, Vector};
+use std::iter::once;
 
 /// Common abstration between `Polygon` and `Pocket`.
 pub trait Shape {
@@ -27,22 +28,21 @@ impl Shape for Polygon {
 
 impl Shape for Pocket {
     fn inner_y(&self) -> f64 {
-        let (y1, y2) = two_mins(self.points().map(|p| p.y)).expect("flat shape");
+        // arcs can go above all points (circles have only one point)
+        let ymax = self.quadrant.limits(1).1;
+        let (y1, y2) = two_mins(self.points().map(|p| p.y).chain(once(ymax))).expect("flat shape");
         (y1 + y2) / 2.0
     }
     fn quadrant(&self) -> &Quadrant {
         &self.quadrant
     }
     fn register_intersections(&self, intersections: &mut Vec<(f64, usize)>, index: usize, y: f64) {
-        let (xmin, xmax) = self.quadrant.limits(0);
-        let s = ElementaryPath::Segment(Segment::new(
-            Point::new(xmin - 0.1, y),
-            Point::new(xmax + 0.1, y),
-        ));
+        // only real crossings are registered (a vertex is shared by two paths)
+        let line = Line::new(Point::new(0.0, y), Vector::new(1.0, 0.0));
         intersections.extend(
-            self.edge
-                .iter()
-                .flat_map(|p| p.intersections_with(&s).map(|i| (i.x, index))),
+            line.intersections_with_pocket(self)
+                .into_iter()
+                .map(|i| (i.x, index)),
         );
     }
 }
@@ -1,6 +1,7 @@
 //! This module contains the `slice` function which is the main
 //! entry point for slicers.
 use holed_polygon::build_holed_polygons;
+use layers::classify_layers;
 use overlap::remove_overlaps;
 use polygon::polygon_builder::build_polygons;
 use std::io::Error;
@@ -13,17 +14,23 @@ pub fn slice<P: AsRef<Path>>(stl_file: P, thickness: f64) -> Result<(), Error> {
     let mut stl = Stl::new(stl_file)?;
     let mut points_hasher = PointsHash::new(0.00001);
     let slices = stl.cut(thickness, &mut points_hasher);
+    let mut layers = Vec::with_capacity(slices.len());
     for slice in slices {
         let remaining_segments = remove_overlaps(slice);
         let polygons = build_polygons(&remaining_segments);
         tycat!(&polygons);
         let holed_polygons = build_holed_polygons(polygons);
         tycat!(&holed_polygons);
+        let mut pockets = Vec::new();
         for holed_polygon in &holed_polygons {
-            let pockets = holed_polygon.offset(0.1, &mut points_hasher);
-            tycat!(pockets);
+            pockets.extend(holed_polygon.offset(0.1, &mut points_hasher));
         }
+        tycat!(&pockets);
+        layers.push(pockets);
+    }
+    let classification = classify_layers(&layers, &mut points_hasher);
+    for layer in &classification {
+        tycat!(layer.sky, layer.earth, layer.sky_and_earth, layer.nothing);
     }
-    unimplemented!("we need to go on with heaven and hell");
     Ok(())
 }
@@ -0,0 +1,93 @@
+//! Supports generation for overhangs (the "sky only" regions).
+use boolean::{pockets_difference, pockets_union};
+use {HoledPocket, HoledPolygon, PointsHash};
+
+/// Maximal distance between computed supports (which contain arcs) and returned polygons.
+const SUPPORT_PRECISION: f64 = 0.001;
+
+/// Compute supports for all given layers (sorted from bottom to top, each of given thickness).
+///
+/// A region is an overhang if it is further than `thickness * tan(overhang_angle)`
+/// from the layer below (the angle is measured from the vertical).
+/// Overhangs are projected down until they reach the bed or the model.
+/// Supports are kept `xy_spacing` away from the model horizontally and
+/// `interface_gap` layers away from it vertically.
+///
+/// We return the supports of each layer.
+///
+/// # Example
+/// ```
+/// use grouille::supports::supports;
+/// use grouille::{HoledPolygon, PointsHash, Polygon};
+/// let mut hasher = PointsHash::new(0.00001);
+/// // a 10x10 table on a 2x2 leg
+/// let leg = HoledPolygon::new(Polygon::square(4.0, 4.0, 2.0), Vec::new());
+/// let table = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), Vec::new());
+/// let layers = vec![vec![leg.clone()], vec![leg.clone()], vec![leg], vec![table]];
+/// let table_supports = supports(&layers, 1.0, 0.0, 0.0, 0, &mut hasher);
+/// assert!(table_supports[3].is_empty());
+/// for layer in &table_supports[..3] {
+///     assert_eq!(layer.len(), 1);
+///     assert_eq!(layer[0].holes.len(), 1); // around the leg
+/// }
+/// // leave one layer below the table
+/// let spaced_supports = supports(&layers, 1.0, 0.0, 0.5, 1, &mut hasher);
+/// assert!(spaced_supports[2].is_empty());
+/// assert_eq!(spaced_supports[1].len(), 1);
+/// ```
+pub fn supports(
+    layers: &[Vec<HoledPolygon>],
+    thickness: f64,
+    overhang_angle: f64,
+    xy_spacing: f64,
+    interface_gap: usize,
+    points_hasher: &mut PointsHash,
+) -> Vec<Vec<HoledPolygon>> {
+    let models: Vec<Vec<HoledPocket>> = layers
+        .iter()
+        .map(|layer| layer.iter().map(|p| p.to_holed_pocket()).collect())
+        .collect();
+    let tolerance = thickness * overhang_angle.tan();
+
+    // go down, accumulating all regions needing support
+    let mut supports = vec![Vec::new(); models.len()];
+    let mut carried = Vec::new();
+    for index in (1..models.len()).rev() {
+        let supported = grow(&models[index - 1], tolerance, points_hasher);
+        let overhangs = pockets_difference(&models[index], &supported, points_hasher);
+        carried = pockets_union(&carried, &overhangs, points_hasher);
+        // what falls on the model stops here
+        carried = pockets_difference(&carried, &models[index - 1], points_hasher);
+        supports[index - 1] = carried.clone();
+    }
+
+    // now keep away from the model
+    supports
+        .iter()
+        .enumerate()
+        .map(|(index, layer_supports)| {
+            let mut forbidden = grow(&models[index], xy_spacing, points_hasher);
+            let first_neighbour = index.saturating_sub(interface_gap);
+            let last_neighbour = (index + interface_gap).min(models.len() - 1);
+            for neighbour in (first_neighbour..=last_neighbour).filter(|&n| n != index) {
+                forbidden = pockets_union(&forbidden, &models[neighbour], points_hasher);
+            }
+            pockets_difference(layer_supports, &forbidden, points_hasher)
+                .iter()
+                .map(|s| s.to_holed_polygon(SUPPORT_PRECISION))
+                .collect()
+        })
+        .collect()
+}
+
+/// Grow given regions by given radius (if positive).
+fn grow(regions: &[HoledPocket], radius: f64, points_hasher: &mut PointsHash) -> Vec<HoledPocket> {
+    if radius <= 0.0 {
+        return regions.to_vec();
+    }
+    let grown: Vec<HoledPocket> = regions
+        .iter()
+        .flat_map(|r| r.outward_offset(radius, points_hasher))
+        .collect();
+    pockets_union(&grown, &[], points_hasher)
+}
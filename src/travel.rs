@@ -0,0 +1,240 @@
+//! Travel moves staying inside a region.
+//!
+//! We keep the tool away from the walls by shrinking the region by a clearance margin.
+//! Shortest paths are then found in the visibility graph of the shrunk region's reflex
+//! vertices (shortest paths only turn around them).
+use boolean::winding;
+use std::iter::once;
+use {HoledPolygon, Pocket, Point, PointsHash, Segment};
+
+/// Maximal distance between the shrunk region (which contains arcs) and its polygonal approximation,
+/// as a fraction of the clearance. Travels might get this much closer to the walls.
+const TRAVEL_PRECISION: f64 = 0.1;
+
+/// Maximal distance for a point to be considered on a wall or on a segment.
+const ON_WALL: f64 = 0.000001;
+
+/// Plans travel moves inside a region.
+#[derive(Debug)]
+pub struct TravelPlanner {
+    /// Boundaries of the region where the tool can travel
+    loops: Vec<Pocket>,
+    /// All boundary segments
+    walls: Vec<Segment>,
+    /// All boundary vertices
+    vertices: Vec<Point>,
+    /// Reflex boundary vertices, where travels can turn
+    nodes: Vec<Point>,
+    /// For each node, all nodes visible from it together with their distance
+    neighbours: Vec<Vec<(usize, f64)>>,
+}
+
+impl TravelPlanner {
+    /// Prepare travel moves inside given region, staying at least at given clearance from its walls.
+    pub fn new(region: &HoledPolygon, clearance: f64, points_hasher: &mut PointsHash) -> Self {
+        let regions = if clearance > 0.0 {
+            region
+                .offset(clearance, points_hasher)
+                .iter()
+                .map(|r| r.to_holed_polygon(clearance * TRAVEL_PRECISION))
+                .collect()
+        } else {
+            vec![region.clone()]
+        };
+        let polygons: Vec<_> = regions
+            .iter()
+            .flat_map(|r| once(&r.outer_polygon).chain(r.holes.iter()))
+            .collect();
+        let mut planner = TravelPlanner {
+            loops: polygons.iter().map(|p| p.to_pocket()).collect(),
+            walls: polygons
+                .iter()
+                .flat_map(|p| {
+                    p.points()
+                        .iter()
+                        .zip(p.points().iter().cycle().skip(1))
+                        .map(|(start, end)| Segment::new(*start, *end))
+                })
+                .collect(),
+            vertices: polygons
+                .iter()
+                .flat_map(|p| p.points().iter().cloned())
+                .collect(),
+            nodes: polygons
+                .iter()
+                .flat_map(|p| reflex_vertices(p.points()))
+                .collect(),
+            neighbours: Vec::new(),
+        };
+        planner.neighbours = (0..planner.nodes.len())
+            .map(|n1| {
+                (0..planner.nodes.len())
+                    .filter(|&n2| {
+                        n1 != n2 && planner.is_visible(&planner.nodes[n1], &planner.nodes[n2])
+                    })
+                    .map(|n2| (n2, planner.nodes[n1].distance_to(&planner.nodes[n2])))
+                    .collect()
+            })
+            .collect();
+        planner
+    }
+
+    /// Return if given point is in the region where the tool can travel.
+    pub fn contains(&self, point: &Point) -> bool {
+        self.walls
+            .iter()
+            .any(|w| w.projection(point).distance < ON_WALL)
+            || winding(point, &self.loops) > 0
+    }
+
+    /// Return if we can travel in straight line between given points.
+    fn is_visible(&self, start: &Point, end: &Point) -> bool {
+        let length = start.distance_to(end);
+        if length < ON_WALL {
+            return true;
+        }
+        let direction = (*end - *start) * (1.0 / length);
+        let crosses = |wall: &Segment| {
+            let wall_direction = wall.end - wall.start;
+            let wall_length = wall_direction.norm();
+            if wall_length < ON_WALL {
+                return false;
+            }
+            let wall_direction = wall_direction * (1.0 / wall_length);
+            let sides = (
+                direction.cross_product(&(wall.start - *start)),
+                direction.cross_product(&(wall.end - *start)),
+            );
+            let wall_sides = (
+                wall_direction.cross_product(&(*start - wall.start)),
+                wall_direction.cross_product(&(*end - wall.start)),
+            );
+            sides.0 * sides.1 < 0.0
+                && sides.0.abs().min(sides.1.abs()) > ON_WALL
+                && wall_sides.0 * wall_sides.1 < 0.0
+                && wall_sides.0.abs().min(wall_sides.1.abs()) > ON_WALL
+        };
+        if self.walls.iter().any(crosses) {
+            return false;
+        }
+        // we can still go outside through a vertex : check between all vertices we touch
+        let mut touched: Vec<f64> = self
+            .vertices
+            .iter()
+            .filter(|v| direction.cross_product(&(**v - *start)).abs() < ON_WALL)
+            .map(|v| direction.scalar_product(&(*v - *start)) / length)
+            .filter(|&t| t > 0.0 && t < 1.0)
+            .chain(vec![0.0, 1.0])
+            .collect();
+        touched.sort_by(|t1, t2| t1.partial_cmp(t2).unwrap());
+        touched
+            .iter()
+            .zip(touched.iter().skip(1))
+            .filter(|(t1, t2)| (*t2 - *t1) * length > ON_WALL)
+            .all(|(t1, t2)| self.contains(&(*start + direction * ((t1 + t2) / 2.0 * length))))
+    }
+
+    /// Return shortest travel from start to end point as a list of points (including both),
+    /// staying inside the region.
+    /// Return `None` if one point is outside the region or if they are in different parts of it.
+    ///
+    /// # Example
+    /// ```
+    /// use grouille::travel::TravelPlanner;
+    /// use grouille::{HoledPolygon, Point, PointsHash, Polygon};
+    /// let mut hasher = PointsHash::new(0.00001);
+    /// let mut hole = Polygon::square(4.0, 4.0, 2.0);
+    /// hole.reverse();
+    /// let region = HoledPolygon::new(Polygon::square(0.0, 0.0, 10.0), vec![hole]);
+    /// let (start, end) = (Point::new(2.0, 5.0), Point::new(8.0, 5.0));
+    /// let length = |points: &[Point]| -> f64 {
+    ///     points.iter().zip(points.iter().skip(1)).map(|(p1, p2)| p1.distance_to(p2)).sum()
+    /// };
+    /// // go around the hole by two of its corners
+    /// let travel = TravelPlanner::new(&region, 0.0, &mut hasher).path(&start, &end).unwrap();
+    /// assert_eq!(travel.len(), 4);
+    /// assert!((length(&travel) - (2.0 * 5.0f64.sqrt() + 2.0)).abs() < 0.00001);
+    /// // a bit longer when keeping away from it
+    /// let planner = TravelPlanner::new(&region, 0.5, &mut hasher);
+    /// let travel = planner.path(&start, &end).unwrap();
+    /// assert!(length(&travel) > 2.0 * 5.0f64.sqrt() + 2.0);
+    /// assert!(planner.path(&start, &Point::new(9.9, 5.0)).is_none());
+    /// ```
+    pub fn path(&self, start: &Point, end: &Point) -> Option<Vec<Point>> {
+        if !self.contains(start) || !self.contains(end) {
+            return None;
+        }
+        if self.is_visible(start, end) {
+            return Some(vec![*start, *end]);
+        }
+        // dijkstra from the end, start and end being the two last vertices
+        let (start_vertex, end_vertex) = (self.nodes.len(), self.nodes.len() + 1);
+        let point = |vertex: usize| {
+            if vertex == start_vertex {
+                start
+            } else if vertex == end_vertex {
+                end
+            } else {
+                &self.nodes[vertex]
+            }
+        };
+        let end_neighbours: Vec<(usize, f64)> = (0..self.nodes.len())
+            .filter(|&v| self.is_visible(end, &self.nodes[v]))
+            .map(|v| (v, end.distance_to(&self.nodes[v])))
+            .collect();
+        let mut distances = vec![f64::INFINITY; self.nodes.len() + 2];
+        let mut next_vertices = vec![None; self.nodes.len() + 2];
+        let mut reached = vec![false; self.nodes.len() + 2];
+        distances[end_vertex] = 0.0;
+        loop {
+            let closest = (0..distances.len())
+                .filter(|&v| !reached[v] && distances[v].is_finite())
+                .min_by(|&v1, &v2| distances[v1].partial_cmp(&distances[v2]).unwrap());
+            let vertex = match closest {
+                Some(vertex) if vertex != start_vertex => vertex,
+                _ => break,
+            };
+            reached[vertex] = true;
+            let neighbours = if vertex == end_vertex {
+                &end_neighbours
+            } else {
+                &self.neighbours[vertex]
+            };
+            let start_distance = if vertex != end_vertex && self.is_visible(start, point(vertex)) {
+                Some((start_vertex, start.distance_to(point(vertex))))
+            } else {
+                None
+            };
+            for &(neighbour, distance) in neighbours.iter().chain(start_distance.iter()) {
+                let new_distance = distances[vertex] + distance;
+                if new_distance < distances[neighbour] {
+                    distances[neighbour] = new_distance;
+                    next_vertices[neighbour] = Some(vertex);
+                }
+            }
+        }
+        if !distances[start_vertex].is_finite() {
+            return None;
+        }
+        let mut travel = vec![*start];
+        let mut current = start_vertex;
+        while let Some(next) = next_vertices[current] {
+            travel.push(*point(next));
+            current = next;
+        }
+        Some(travel)
+    }
+}
+
+/// Iterate on all reflex vertices of given loop (where it turns left, away from the region
+/// on its right).
+fn reflex_vertices<'a>(points: &'a [Point]) -> impl Iterator<Item = Point> + 'a {
+    let count = points.len();
+    (0..count)
+        .filter(move |&i| {
+            let before = points[i] - points[(i + count - 1) % count];
+            let after = points[(i + 1) % count] - points[i];
+            before.cross_product(&after) < 0.0
+        })
+        .map(move |i| points[i])
+}
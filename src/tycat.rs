@@ -10,8 +10,8 @@ use std::iter::once;
 use std::process::Command;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use {
-    Arc, ElementaryPath, HoledPocket, HoledPolygon, Pocket, Point, Polygon, Quadrant, Segment,
-    Vector,
+    Arc, Bezier, Circle, ElementaryPath, HoledPocket, HoledPolygon, Path, Pocket, Point, Polygon,
+    Quadrant, Segment,
 };
 
 /// Anything displayable in terminology needs to implement this trait.
@@ -131,6 +131,44 @@ impl Tycat for HoledPolygon {
     }
 }
 
+/// Turn elementary path into svg path command (starting point is not included).
+fn path_command(path: &ElementaryPath) -> String {
+    match *path {
+        ElementaryPath::Segment(ref s) => format!(" L {} {}", s.end.x, s.end.y),
+        ElementaryPath::Arc(ref a) => format!(
+            " A {},{} 0 {},{} {},{}",
+            a.radius,
+            a.radius,
+            if a.angle() > PI { 1 } else { 0 },
+            if a.clockwise { 1 } else { 0 },
+            a.end.x,
+            a.end.y
+        ),
+        ElementaryPath::Bezier(ref b) => format!(
+            " C {},{} {},{} {},{}",
+            b.control1.x, b.control1.y, b.control2.x, b.control2.y, b.end.x, b.end.y
+        ),
+        ElementaryPath::Circle(ref c) => {
+            // svg cannot draw full circles as one arc so we go through the opposite point
+            let opposite = c.start.rotate_around(&c.center, PI);
+            let sweep_flag = if c.clockwise { 1 } else { 0 };
+            format!(
+                " A {},{} 0 1,{} {},{} A {},{} 0 1,{} {},{}",
+                c.radius,
+                c.radius,
+                sweep_flag,
+                opposite.x,
+                opposite.y,
+                c.radius,
+                c.radius,
+                sweep_flag,
+                c.start.x,
+                c.start.y
+            )
+        }
+    }
+}
+
 /// Turn pocket into svg path.
 fn pocket_path<'a>(pocket: &'a Pocket) -> impl Iterator<Item = String> + 'a {
     pocket
@@ -138,18 +176,8 @@ fn pocket_path<'a>(pocket: &'a Pocket) -> impl Iterator<Item = String> + 'a {
         .first()
         .map(|first_path| {
             let starting_point = first_path.start();
-            once(format!("M{},{}", starting_point.x, starting_point.y)).chain(
-                pocket.edge.iter().map(|p| match *p {
-                    ElementaryPath::Segment(ref s) => format!(" L {} {}", s.end.x, s.end.y),
-                    ElementaryPath::Arc(ref a) => {
-                        let sweep_flag = if a.angle() > PI { 1 } else { 0 };
-                        format!(
-                            " A {},{} 0 0,{} {},{}",
-                            a.radius, a.radius, sweep_flag, a.end.x, a.end.y
-                        )
-                    }
-                }),
-            )
+            once(format!("M{},{}", starting_point.x, starting_point.y))
+                .chain(pocket.edge.iter().map(path_command))
         })
         .into_iter()
         .flatten()
@@ -180,50 +208,77 @@ impl Tycat for Arc {
     }
     fn svg_string(&self) -> String {
         let center_string = self.center.svg_string();
-        // go always for the small arc
-        let sweep_flag = if self.angle() > PI { 1 } else { 0 };
         let arc_string = format!(
-            "<path d=\"M{},{} A{},{} 0 0,{} {},{}\" fill=\"none\"/>",
+            "<path d=\"M{},{}{}\" fill=\"none\"/>",
             self.start.x,
             self.start.y,
-            self.radius,
-            self.radius,
-            sweep_flag,
-            self.end.x,
-            self.end.y
+            path_command(&ElementaryPath::Arc(*self))
         );
         // now draw a small arrow indicating orientation
-        let mut middle_angle =
-            ((self.start - self.center).angle() + (self.end - self.center).angle()) / 2.0;
-        // we need to figure out where is the middle point between two candidates
-        let possible_points = [
-            self.center + Vector::polar(self.radius, middle_angle),
-            self.center + Vector::polar(self.radius, middle_angle + PI),
-        ];
-        let distances = [
-            self.start.distance_to(&possible_points[0]),
-            self.start.distance_to(&possible_points[1]),
-        ];
-        let tangent_point = if distances[0] < distances[1] {
-            possible_points[0]
-        } else {
-            middle_angle += PI;
-            possible_points[1]
-        };
-        let tangent_angle = if sweep_flag == 1 {
-            middle_angle + FRAC_PI_2
-        } else {
-            middle_angle - FRAC_PI_2
-        };
-        let arrow_string = format!(
-            "<use xlink:href=\"#a\" x=\"{}\" y=\"{}\" transform=\"rotate({} {} {})\"/>",
-            tangent_point.x,
-            tangent_point.y,
-            tangent_angle * 180.0 / PI,
-            tangent_point.x,
-            tangent_point.y
+        let direction = if self.clockwise { 1.0 } else { -1.0 };
+        let middle_point = self
+            .start
+            .rotate_around(&self.center, direction * self.angle() / 2.0);
+        let tangent_angle = (middle_point - self.center).angle() + direction * FRAC_PI_2;
+        center_string + &arc_string + &arrow_string(&middle_point, tangent_angle)
+    }
+}
+
+impl Tycat for Circle {
+    fn quadrant(&self) -> Quadrant {
+        Quadrant {
+            mins: [self.center.x - self.radius, self.center.y - self.radius],
+            maxs: [self.center.x + self.radius, self.center.y + self.radius],
+        }
+    }
+    fn svg_string(&self) -> String {
+        let circle_string = format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\"/>",
+            self.center.x, self.center.y, self.radius
         );
-        center_string + &arc_string + &arrow_string
+        // draw starting point and an arrow indicating orientation
+        let direction = if self.clockwise { 1.0 } else { -1.0 };
+        let tangent_angle = (self.start - self.center).angle() + direction * FRAC_PI_2;
+        self.center.svg_string()
+            + &self.start.svg_string()
+            + &circle_string
+            + &arrow_string(&self.start, tangent_angle)
+    }
+}
+
+/// Return svg string for an arrow at given point, pointing in given direction.
+fn arrow_string(point: &Point, angle: f64) -> String {
+    format!(
+        "<use xlink:href=\"#a\" x=\"{}\" y=\"{}\" transform=\"rotate({} {} {})\"/>",
+        point.x,
+        point.y,
+        angle.to_degrees(),
+        point.x,
+        point.y
+    )
+}
+
+impl Tycat for Bezier {
+    fn quadrant(&self) -> Quadrant {
+        // the curve is inside the convex hull of its control points
+        Quadrant::new()
+            .add(&self.start)
+            .add(&self.control1)
+            .add(&self.control2)
+            .add(&self.end)
+    }
+    fn svg_string(&self) -> String {
+        format!(
+            "<path d=\"M{},{} C {},{} {},{} {},{}\" fill=\"none\"/>",
+            self.start.x,
+            self.start.y,
+            self.control1.x,
+            self.control1.y,
+            self.control2.x,
+            self.control2.y,
+            self.end.x,
+            self.end.y
+        )
     }
 }
 
@@ -231,12 +286,16 @@ impl Tycat for ElementaryPath {
     fn quadrant(&self) -> Quadrant {
         match *self {
             ElementaryPath::Arc(ref a) => a.quadrant(),
+            ElementaryPath::Bezier(ref b) => b.quadrant(),
+            ElementaryPath::Circle(ref c) => c.quadrant(),
             ElementaryPath::Segment(ref s) => s.quadrant(),
         }
     }
     fn svg_string(&self) -> String {
         match *self {
             ElementaryPath::Arc(ref a) => a.svg_string(),
+            ElementaryPath::Bezier(ref b) => b.svg_string(),
+            ElementaryPath::Circle(ref c) => c.svg_string(),
             ElementaryPath::Segment(ref s) => s.svg_string(),
         }
     }
@@ -247,30 +306,33 @@ impl Tycat for Pocket {
         self.quadrant
     }
     fn svg_string(&self) -> String {
-        if let Some(first_path) = self.edge.first() {
-            let starting_point = first_path.start();
-            once(format!(
-                "<path d=\"M{},{}",
-                starting_point.x, starting_point.y
-            ))
-            .chain(self.edge.iter().map(|p| match *p {
-                ElementaryPath::Segment(ref s) => format!(" L {} {}", s.end.x, s.end.y),
-                ElementaryPath::Arc(ref a) => {
-                    let sweep_flag = if a.angle() > PI { 1 } else { 0 };
-                    format!(
-                        " A {},{} 0 0,{} {},{}",
-                        a.radius, a.radius, sweep_flag, a.end.x, a.end.y
-                    )
-                }
-            }))
-            .chain(once("\"/>".to_string()))
-            .collect()
-        } else {
+        if self.edge.is_empty() {
             String::new()
+        } else {
+            once("<path d=\"".to_string())
+                .chain(pocket_path(self))
+                .chain(once("\"/>".to_string()))
+                .collect()
         }
     }
 }
 
+impl Tycat for Path {
+    fn quadrant(&self) -> Quadrant {
+        self.quadrant
+    }
+    fn svg_string(&self) -> String {
+        let starting_point = self.start();
+        once(format!(
+            "<path d=\"M{},{}",
+            starting_point.x, starting_point.y
+        ))
+        .chain(self.paths().iter().map(path_command))
+        .chain(once("\" fill=\"none\"/>".to_string()))
+        .collect()
+    }
+}
+
 /* below this point is all the dirty svg intrinsics */
 
 static FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
@@ -9,6 +9,13 @@ pub mod iterators;
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct Angle(f64);
 
+impl Angle {
+    /// Return angle value (in [0, 2PI)).
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
 impl Eq for Angle {}
 impl Ord for Angle {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {